@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_bids_auction_id;
+DROP INDEX IF EXISTS idx_bids_is_valid;
+
+ALTER TABLE auctions DROP COLUMN IF EXISTS invalid_bid_count;
+
+ALTER TABLE bids DROP COLUMN IF EXISTS violations;
+ALTER TABLE bids DROP COLUMN IF EXISTS is_valid;
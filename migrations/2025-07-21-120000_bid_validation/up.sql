@@ -0,0 +1,7 @@
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS is_valid BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS violations TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE auctions ADD COLUMN IF NOT EXISTS invalid_bid_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_bids_auction_id ON bids(auction_id);
+CREATE INDEX IF NOT EXISTS idx_bids_is_valid ON bids(is_valid);
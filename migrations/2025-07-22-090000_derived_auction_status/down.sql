@@ -0,0 +1,21 @@
+DROP INDEX IF EXISTS idx_auctions_end_time;
+
+-- Enum values can't be dropped, so the type is recreated and derived statuses fall back to 'created'
+ALTER TABLE auctions ALTER COLUMN status DROP DEFAULT;
+ALTER TYPE AuctionStatus RENAME TO AuctionStatus_old;
+
+CREATE TYPE AuctionStatus AS ENUM (
+    'created',
+    'cancelled',
+    'finalized'
+);
+
+ALTER TABLE auctions ALTER COLUMN status TYPE AuctionStatus USING (
+    CASE
+        WHEN status::TEXT IN ('active', 'ended', 'ended-no-bids') THEN 'created'
+        ELSE status::TEXT
+    END
+)::AuctionStatus;
+ALTER TABLE auctions ALTER COLUMN status SET DEFAULT 'created';
+
+DROP TYPE AuctionStatus_old;
@@ -0,0 +1,5 @@
+ALTER TYPE AuctionStatus ADD VALUE IF NOT EXISTS 'active';
+ALTER TYPE AuctionStatus ADD VALUE IF NOT EXISTS 'ended';
+ALTER TYPE AuctionStatus ADD VALUE IF NOT EXISTS 'ended-no-bids';
+
+CREATE INDEX IF NOT EXISTS idx_auctions_end_time ON auctions(end_time);
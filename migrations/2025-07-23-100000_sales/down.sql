@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_sales_domain_name;
+DROP INDEX IF EXISTS idx_sales_seller;
+DROP INDEX IF EXISTS idx_sales_buyer;
+DROP INDEX IF EXISTS idx_sales_created_at;
+
+DROP TABLE IF EXISTS sales;
+DROP TYPE IF EXISTS SaleType;
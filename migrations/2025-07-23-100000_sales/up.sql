@@ -0,0 +1,49 @@
+CREATE TYPE SaleType AS ENUM (
+    'offer',
+    'counter-offer',
+    'auction'
+);
+
+CREATE TABLE IF NOT EXISTS sales (
+    id SERIAL PRIMARY KEY,
+    domain_name VARCHAR NOT NULL,
+    seller VARCHAR NOT NULL,
+    buyer VARCHAR NOT NULL,
+    price VARCHAR NOT NULL,
+    sale_type SaleType NOT NULL,
+    -- Empty for the sales backfilled below
+    checkpoint BIGINT,
+    tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sales_domain_name ON sales(domain_name);
+CREATE INDEX IF NOT EXISTS idx_sales_seller ON sales(seller);
+CREATE INDEX IF NOT EXISTS idx_sales_buyer ON sales(buyer);
+CREATE INDEX IF NOT EXISTS idx_sales_created_at ON sales(created_at);
+
+-- Sales made before this table existed, from the events indexed so far. Their checkpoints aren't
+-- known. The seller of a counter offer is the owner who made the last counter before it was
+-- accepted.
+INSERT INTO sales (domain_name, seller, buyer, price, sale_type, checkpoint, tx_digest, created_at)
+SELECT domain_name, seller, buyer, price, sale_type::SaleType, NULL, tx_digest, created_at
+FROM (
+    SELECT domain_name, owner AS seller, address AS buyer, value AS price, 'offer' AS sale_type,
+        tx_digest, created_at, id
+    FROM offer_accepted
+    UNION ALL
+    SELECT a.domain_name, m.owner, a.address, a.value, 'counter-offer', a.tx_digest, a.created_at,
+        a.id
+    FROM accept_counter_offer a
+    JOIN LATERAL (
+        SELECT owner FROM make_counter_offer m
+        WHERE m.domain_name = a.domain_name AND m.address = a.address AND m.created_at <= a.created_at
+        ORDER BY m.created_at DESC, m.id DESC
+        LIMIT 1
+    ) m ON TRUE
+    UNION ALL
+    SELECT domain_name, owner, winner, amount, 'auction', last_tx_digest, updated_at, 0
+    FROM auctions
+    WHERE status = 'finalized' AND winner IS NOT NULL AND amount IS NOT NULL
+) backfill
+ORDER BY created_at, id;
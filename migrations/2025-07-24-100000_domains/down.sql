@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_domains_active_auction_id;
+
+DROP TABLE IF EXISTS domains;
@@ -0,0 +1,63 @@
+CREATE TABLE IF NOT EXISTS domains (
+    domain_name VARCHAR NOT NULL PRIMARY KEY,
+    open_offer_count INTEGER NOT NULL DEFAULT 0,
+    best_offer VARCHAR,
+    active_auction_id VARCHAR,
+    last_sale_price VARCHAR,
+    last_sale_at TIMESTAMPTZ,
+    total_volume VARCHAR NOT NULL DEFAULT '0',
+    sale_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_domains_active_auction_id ON domains(active_auction_id);
+
+-- Backfill from the data indexed so far
+INSERT INTO domains (domain_name, updated_at)
+SELECT domain_name, NOW() FROM offers
+UNION
+SELECT domain_name, NOW() FROM auctions
+UNION
+SELECT domain_name, NOW() FROM sales
+ON CONFLICT (domain_name) DO NOTHING;
+
+UPDATE domains d SET
+    open_offer_count = o.open_offer_count,
+    best_offer = o.best_offer
+FROM (
+    SELECT domain_name, COUNT(*) AS open_offer_count, MAX(value::NUMERIC)::VARCHAR AS best_offer
+    FROM offers
+    WHERE status IN ('placed', 'countered')
+    GROUP BY domain_name
+) o
+WHERE d.domain_name = o.domain_name;
+
+UPDATE domains d SET
+    active_auction_id = a.auction_id
+FROM (
+    SELECT DISTINCT ON (domain_name) domain_name, auction_id
+    FROM auctions
+    WHERE status IN ('created', 'active', 'ended')
+    ORDER BY domain_name, created_at DESC
+) a
+WHERE d.domain_name = a.domain_name;
+
+UPDATE domains d SET
+    total_volume = s.total_volume,
+    sale_count = s.sale_count
+FROM (
+    SELECT domain_name, SUM(price::NUMERIC)::VARCHAR AS total_volume, COUNT(*) AS sale_count
+    FROM sales
+    GROUP BY domain_name
+) s
+WHERE d.domain_name = s.domain_name;
+
+UPDATE domains d SET
+    last_sale_price = s.price,
+    last_sale_at = s.created_at
+FROM (
+    SELECT DISTINCT ON (domain_name) domain_name, price, created_at
+    FROM sales
+    ORDER BY domain_name, checkpoint DESC NULLS LAST, id DESC
+) s
+WHERE d.domain_name = s.domain_name;
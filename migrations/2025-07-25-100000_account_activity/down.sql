@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_account_activity_address;
+DROP INDEX IF EXISTS idx_account_activity_domain_name;
+
+DROP TABLE IF EXISTS accounts;
+DROP TABLE IF EXISTS account_activity;
+DROP TYPE IF EXISTS ActivityRole;
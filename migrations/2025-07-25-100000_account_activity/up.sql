@@ -0,0 +1,54 @@
+CREATE TYPE ActivityRole AS ENUM (
+    'buyer',
+    'owner',
+    'bidder',
+    'seller'
+);
+
+CREATE TABLE IF NOT EXISTS account_activity (
+    id SERIAL PRIMARY KEY,
+    address VARCHAR NOT NULL,
+    role ActivityRole NOT NULL,
+    action VARCHAR NOT NULL,
+    domain_name VARCHAR NOT NULL,
+    counterparty VARCHAR,
+    amount VARCHAR,
+    checkpoint BIGINT NOT NULL,
+    tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_activity_address ON account_activity(address, created_at);
+CREATE INDEX IF NOT EXISTS idx_account_activity_domain_name ON account_activity(domain_name);
+
+CREATE TABLE IF NOT EXISTS accounts (
+    address VARCHAR NOT NULL PRIMARY KEY,
+    total_spent VARCHAR NOT NULL DEFAULT '0',
+    total_received VARCHAR NOT NULL DEFAULT '0',
+    open_commitments VARCHAR NOT NULL DEFAULT '0',
+    purchase_count INTEGER NOT NULL DEFAULT 0,
+    sale_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Backfill sale totals from the sales indexed so far, open commitments are filled in as accounts get activity
+INSERT INTO accounts (address, total_spent, purchase_count, updated_at)
+SELECT buyer, SUM(price::NUMERIC)::VARCHAR, COUNT(*), NOW()
+FROM sales
+GROUP BY buyer
+ON CONFLICT (address) DO NOTHING;
+
+INSERT INTO accounts (address, updated_at)
+SELECT DISTINCT seller, NOW()
+FROM sales
+ON CONFLICT (address) DO NOTHING;
+
+UPDATE accounts a SET
+    total_received = s.total_received,
+    sale_count = s.sale_count
+FROM (
+    SELECT seller, SUM(price::NUMERIC)::VARCHAR AS total_received, COUNT(*) AS sale_count
+    FROM sales
+    GROUP BY seller
+) s
+WHERE a.address = s.seller;
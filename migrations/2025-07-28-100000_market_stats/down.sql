@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_offers_created_at;
+DROP INDEX IF EXISTS idx_auctions_created_at;
+
+DROP TABLE IF EXISTS epochs;
+DROP TABLE IF EXISTS epoch_stats;
+DROP TABLE IF EXISTS daily_stats;
@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS daily_stats (
+    day DATE NOT NULL PRIMARY KEY,
+    offers_placed INTEGER NOT NULL DEFAULT 0,
+    offers_accepted INTEGER NOT NULL DEFAULT 0,
+    auctions_created INTEGER NOT NULL DEFAULT 0,
+    auctions_finalized INTEGER NOT NULL DEFAULT 0,
+    unique_buyers INTEGER NOT NULL DEFAULT 0,
+    unique_sellers INTEGER NOT NULL DEFAULT 0,
+    volume VARCHAR NOT NULL DEFAULT '0',
+    median_sale_price VARCHAR,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS epoch_stats (
+    epoch BIGINT NOT NULL PRIMARY KEY,
+    offers_placed INTEGER NOT NULL DEFAULT 0,
+    offers_accepted INTEGER NOT NULL DEFAULT 0,
+    auctions_created INTEGER NOT NULL DEFAULT 0,
+    auctions_finalized INTEGER NOT NULL DEFAULT 0,
+    unique_buyers INTEGER NOT NULL DEFAULT 0,
+    unique_sellers INTEGER NOT NULL DEFAULT 0,
+    volume VARCHAR NOT NULL DEFAULT '0',
+    median_sale_price VARCHAR,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Time range of the checkpoints seen so far in each epoch, used to aggregate per epoch
+CREATE TABLE IF NOT EXISTS epochs (
+    epoch BIGINT NOT NULL PRIMARY KEY,
+    first_checkpoint_at TIMESTAMPTZ NOT NULL,
+    last_checkpoint_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_offers_created_at ON offers(created_at);
+CREATE INDEX IF NOT EXISTS idx_auctions_created_at ON auctions(created_at);
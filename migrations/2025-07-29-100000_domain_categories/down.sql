@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS idx_domains_label_length;
+DROP INDEX IF EXISTS idx_domains_char_class;
+
+ALTER TABLE domains DROP COLUMN IF EXISTS is_dictionary_word;
+ALTER TABLE domains DROP COLUMN IF EXISTS is_subdomain;
+ALTER TABLE domains DROP COLUMN IF EXISTS has_repeating_pattern;
+ALTER TABLE domains DROP COLUMN IF EXISTS is_palindrome;
+ALTER TABLE domains DROP COLUMN IF EXISTS char_class;
+ALTER TABLE domains DROP COLUMN IF EXISTS label_length;
@@ -0,0 +1,9 @@
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS label_length INTEGER;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS char_class VARCHAR;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS is_palindrome BOOLEAN;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS has_repeating_pattern BOOLEAN;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS is_subdomain BOOLEAN;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS is_dictionary_word BOOLEAN;
+
+CREATE INDEX IF NOT EXISTS idx_domains_label_length ON domains(label_length);
+CREATE INDEX IF NOT EXISTS idx_domains_char_class ON domains(char_class);
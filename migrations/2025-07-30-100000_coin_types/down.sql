@@ -0,0 +1,41 @@
+ALTER TABLE domains DROP COLUMN IF EXISTS last_sale_coin_type;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS best_offer VARCHAR;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS total_volume VARCHAR NOT NULL DEFAULT '0';
+
+UPDATE domains d SET
+    best_offer = s.best_offer,
+    total_volume = s.total_volume
+FROM domain_coin_stats s
+WHERE d.domain_name = s.domain_name AND s.coin_type = '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+
+DROP TABLE IF EXISTS domain_coin_stats;
+
+DELETE FROM epoch_stats WHERE coin_type <> '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE epoch_stats DROP CONSTRAINT epoch_stats_pkey;
+ALTER TABLE epoch_stats ADD PRIMARY KEY (epoch);
+ALTER TABLE epoch_stats DROP COLUMN IF EXISTS coin_type;
+
+DELETE FROM daily_stats WHERE coin_type <> '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE daily_stats DROP CONSTRAINT daily_stats_pkey;
+ALTER TABLE daily_stats ADD PRIMARY KEY (day);
+ALTER TABLE daily_stats DROP COLUMN IF EXISTS coin_type;
+
+DELETE FROM accounts WHERE coin_type <> '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE accounts DROP CONSTRAINT accounts_pkey;
+ALTER TABLE accounts ADD PRIMARY KEY (address);
+ALTER TABLE accounts DROP COLUMN IF EXISTS coin_type;
+
+DROP INDEX IF EXISTS idx_offers_coin_type;
+DROP INDEX IF EXISTS idx_sales_coin_type;
+
+ALTER TABLE account_activity DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE sales DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE bids DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE auctions DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE offers DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE accept_counter_offer DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE make_counter_offer DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE offer_declined DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE offer_accepted DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE offer_cancelled DROP COLUMN IF EXISTS coin_type;
+ALTER TABLE offer_placed DROP COLUMN IF EXISTS coin_type;
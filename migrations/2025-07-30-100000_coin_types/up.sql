@@ -0,0 +1,48 @@
+-- Everything indexed before coin types were tracked was denominated in SUI
+ALTER TABLE offer_placed ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE offer_cancelled ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE offer_accepted ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE offer_declined ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE make_counter_offer ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE accept_counter_offer ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE auctions ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE sales ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE account_activity ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+
+CREATE INDEX IF NOT EXISTS idx_offers_coin_type ON offers(coin_type);
+CREATE INDEX IF NOT EXISTS idx_sales_coin_type ON sales(coin_type);
+
+-- Aggregates are kept per coin, so amounts in different coins never get added together
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE accounts DROP CONSTRAINT accounts_pkey;
+ALTER TABLE accounts ADD PRIMARY KEY (address, coin_type);
+
+ALTER TABLE daily_stats ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE daily_stats DROP CONSTRAINT daily_stats_pkey;
+ALTER TABLE daily_stats ADD PRIMARY KEY (day, coin_type);
+
+ALTER TABLE epoch_stats ADD COLUMN IF NOT EXISTS coin_type VARCHAR NOT NULL DEFAULT '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
+ALTER TABLE epoch_stats DROP CONSTRAINT epoch_stats_pkey;
+ALTER TABLE epoch_stats ADD PRIMARY KEY (epoch, coin_type);
+
+CREATE TABLE IF NOT EXISTS domain_coin_stats (
+    domain_name VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    open_offer_count INTEGER NOT NULL DEFAULT 0,
+    best_offer VARCHAR,
+    total_volume VARCHAR NOT NULL DEFAULT '0',
+    sale_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (domain_name, coin_type)
+);
+
+INSERT INTO domain_coin_stats (domain_name, coin_type, open_offer_count, best_offer, total_volume, sale_count, updated_at)
+SELECT domain_name, '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI', open_offer_count, best_offer, total_volume, sale_count, updated_at
+FROM domains;
+
+ALTER TABLE domains DROP COLUMN IF EXISTS best_offer;
+ALTER TABLE domains DROP COLUMN IF EXISTS total_volume;
+ALTER TABLE domains ADD COLUMN IF NOT EXISTS last_sale_coin_type VARCHAR;
+UPDATE domains SET last_sale_coin_type = '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI' WHERE last_sale_price IS NOT NULL;
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_name_records_nft_id;
+DROP INDEX IF EXISTS idx_name_records_target_address;
+DROP INDEX IF EXISTS idx_name_records_expires_at;
+
+DROP TABLE IF EXISTS name_records;
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS name_records (
+    domain_name VARCHAR PRIMARY KEY,
+    nft_id VARCHAR NOT NULL,
+    target_address VARCHAR,
+    expiration_timestamp_ms BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    registered_at TIMESTAMPTZ NOT NULL,
+    last_renewed_at TIMESTAMPTZ,
+    removed_at TIMESTAMPTZ,
+    checkpoint BIGINT NOT NULL,
+    last_tx_digest VARCHAR NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_name_records_nft_id ON name_records(nft_id);
+CREATE INDEX IF NOT EXISTS idx_name_records_target_address ON name_records(target_address);
+CREATE INDEX IF NOT EXISTS idx_name_records_expires_at ON name_records(expires_at);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_registration_owners_domain_name;
+DROP INDEX IF EXISTS idx_registration_owners_owner;
+
+DROP TABLE IF EXISTS registration_owners;
+DROP TYPE IF EXISTS OwnerKind;
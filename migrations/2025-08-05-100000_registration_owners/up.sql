@@ -0,0 +1,23 @@
+CREATE TYPE OwnerKind AS ENUM (
+    'address',
+    'object',
+    'shared',
+    'immutable',
+    'wrapped',
+    'deleted'
+);
+
+CREATE TABLE IF NOT EXISTS registration_owners (
+    nft_id VARCHAR PRIMARY KEY,
+    domain_name VARCHAR NOT NULL,
+    owner_kind OwnerKind NOT NULL,
+    owner VARCHAR,
+    expiration_timestamp_ms BIGINT NOT NULL,
+    checkpoint BIGINT NOT NULL,
+    last_tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_registration_owners_domain_name ON registration_owners(domain_name);
+CREATE INDEX IF NOT EXISTS idx_registration_owners_owner ON registration_owners(owner);
@@ -0,0 +1,27 @@
+ALTER TABLE registration_owners DROP COLUMN IF EXISTS transferred_at;
+ALTER TABLE registration_owners DROP COLUMN IF EXISTS holder;
+
+ALTER TABLE offers DROP COLUMN IF EXISTS stale_reason;
+
+-- Enum values can't be dropped, so the type is recreated and stale offers fall back to 'placed'
+ALTER TABLE offers ALTER COLUMN status DROP DEFAULT;
+ALTER TYPE OfferStatus RENAME TO OfferStatus_old;
+
+CREATE TYPE OfferStatus AS ENUM (
+    'placed',
+    'cancelled',
+    'accepted',
+    'declined',
+    'countered',
+    'accepted-countered'
+);
+
+ALTER TABLE offers ALTER COLUMN status TYPE OfferStatus USING (
+    CASE
+        WHEN status::TEXT = 'stale' THEN 'placed'
+        ELSE status::TEXT
+    END
+)::OfferStatus;
+ALTER TABLE offers ALTER COLUMN status SET DEFAULT 'placed';
+
+DROP TYPE OfferStatus_old;
@@ -0,0 +1,8 @@
+ALTER TYPE OfferStatus ADD VALUE IF NOT EXISTS 'stale';
+
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS stale_reason VARCHAR;
+
+ALTER TABLE registration_owners ADD COLUMN IF NOT EXISTS holder VARCHAR;
+ALTER TABLE registration_owners ADD COLUMN IF NOT EXISTS transferred_at TIMESTAMPTZ;
+
+UPDATE registration_owners SET holder = owner WHERE owner_kind = 'address';
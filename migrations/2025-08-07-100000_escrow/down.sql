@@ -0,0 +1,12 @@
+DROP VIEW IF EXISTS total_value_locked;
+DROP VIEW IF EXISTS value_locked;
+
+ALTER TABLE auctions DROP COLUMN IF EXISTS locked_value;
+ALTER TABLE offers DROP COLUMN IF EXISTS locked_value;
+
+DROP INDEX IF EXISTS idx_escrow_movements_reference;
+DROP INDEX IF EXISTS idx_escrow_movements_address;
+
+DROP TABLE IF EXISTS escrow_movements;
+DROP TYPE IF EXISTS EscrowDirection;
+DROP TYPE IF EXISTS EscrowKind;
@@ -0,0 +1,72 @@
+CREATE TYPE EscrowKind AS ENUM (
+    'offer',
+    'auction'
+);
+
+CREATE TYPE EscrowDirection AS ENUM (
+    'lock',
+    'release'
+);
+
+CREATE TABLE IF NOT EXISTS escrow_movements (
+    id SERIAL PRIMARY KEY,
+    escrow_kind EscrowKind NOT NULL,
+    -- Offer id or auction id the funds are locked in
+    reference VARCHAR NOT NULL,
+    address VARCHAR NOT NULL,
+    domain_name VARCHAR NOT NULL,
+    direction EscrowDirection NOT NULL,
+    reason VARCHAR NOT NULL,
+    amount VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    checkpoint BIGINT NOT NULL,
+    tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_escrow_movements_reference ON escrow_movements(escrow_kind, reference);
+CREATE INDEX IF NOT EXISTS idx_escrow_movements_address ON escrow_movements(address, coin_type);
+
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS locked_value VARCHAR NOT NULL DEFAULT '0';
+ALTER TABLE auctions ADD COLUMN IF NOT EXISTS locked_value VARCHAR NOT NULL DEFAULT '0';
+
+-- Funds still locked in offers and auctions indexed so far. Offers lock their initial value until
+-- they are cancelled, declined or accepted, stale offers included. Auctions lock their highest bid.
+INSERT INTO escrow_movements (escrow_kind, reference, address, domain_name, direction, reason, amount, coin_type, checkpoint, tx_digest, created_at)
+SELECT 'offer', id::VARCHAR, buyer, domain_name, 'lock', 'backfill', initial_value, coin_type, 0, last_tx_digest, created_at
+FROM offers
+WHERE status IN ('placed', 'countered', 'stale');
+
+UPDATE offers SET locked_value = initial_value WHERE status IN ('placed', 'countered', 'stale');
+
+INSERT INTO escrow_movements (escrow_kind, reference, address, domain_name, direction, reason, amount, coin_type, checkpoint, tx_digest, created_at)
+SELECT DISTINCT ON (auctions.auction_id)
+    'auction', auctions.auction_id, bids.bidder, auctions.domain_name, 'lock', 'backfill', bids.amount, auctions.coin_type, 0, bids.tx_digest, bids.created_at
+FROM auctions
+JOIN bids ON bids.auction_id = auctions.auction_id AND bids.is_valid
+WHERE auctions.status IN ('created', 'active', 'ended')
+ORDER BY auctions.auction_id, bids.amount::NUMERIC DESC;
+
+UPDATE auctions SET locked_value = escrow_movements.amount
+FROM escrow_movements
+WHERE escrow_movements.escrow_kind = 'auction' AND escrow_movements.reference = auctions.auction_id;
+
+CREATE OR REPLACE VIEW value_locked AS
+SELECT
+    address,
+    coin_type,
+    SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END)::VARCHAR AS locked
+FROM escrow_movements
+GROUP BY address, coin_type
+HAVING SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END) <> 0;
+
+CREATE OR REPLACE VIEW total_value_locked AS
+SELECT
+    coin_type,
+    SUM(CASE WHEN escrow_kind = 'offer' AND direction = 'lock' THEN amount::NUMERIC
+             WHEN escrow_kind = 'offer' THEN -amount::NUMERIC ELSE 0 END)::VARCHAR AS offers_locked,
+    SUM(CASE WHEN escrow_kind = 'auction' AND direction = 'lock' THEN amount::NUMERIC
+             WHEN escrow_kind = 'auction' THEN -amount::NUMERIC ELSE 0 END)::VARCHAR AS auctions_locked,
+    SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END)::VARCHAR AS locked
+FROM escrow_movements
+GROUP BY coin_type;
@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS idx_offer_placed_tx_digest;
+DROP INDEX IF EXISTS idx_offer_cancelled_tx_digest;
+DROP INDEX IF EXISTS idx_offer_accepted_tx_digest;
+DROP INDEX IF EXISTS idx_offer_declined_tx_digest;
+DROP INDEX IF EXISTS idx_make_counter_offer_tx_digest;
+DROP INDEX IF EXISTS idx_accept_counter_offer_tx_digest;
+DROP INDEX IF EXISTS idx_bids_tx_digest;
+DROP INDEX IF EXISTS idx_sales_tx_digest;
+
+DROP INDEX IF EXISTS idx_transactions_sender;
+DROP INDEX IF EXISTS idx_transactions_checkpoint;
+
+DROP TABLE IF EXISTS transactions;
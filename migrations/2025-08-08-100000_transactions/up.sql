@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS transactions (
+    tx_digest VARCHAR PRIMARY KEY,
+    sender VARCHAR NOT NULL,
+    gas_budget VARCHAR NOT NULL,
+    gas_price VARCHAR NOT NULL,
+    computation_cost VARCHAR NOT NULL,
+    storage_cost VARCHAR NOT NULL,
+    storage_rebate VARCHAR NOT NULL,
+    non_refundable_storage_fee VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    error VARCHAR,
+    event_count INTEGER NOT NULL,
+    checkpoint BIGINT NOT NULL,
+    epoch BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_sender ON transactions(sender);
+CREATE INDEX IF NOT EXISTS idx_transactions_checkpoint ON transactions(checkpoint);
+
+-- Event rows link to their transaction through tx_digest
+CREATE INDEX IF NOT EXISTS idx_offer_placed_tx_digest ON offer_placed(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_offer_cancelled_tx_digest ON offer_cancelled(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_offer_accepted_tx_digest ON offer_accepted(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_offer_declined_tx_digest ON offer_declined(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_make_counter_offer_tx_digest ON make_counter_offer(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_accept_counter_offer_tx_digest ON accept_counter_offer(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_bids_tx_digest ON bids(tx_digest);
+CREATE INDEX IF NOT EXISTS idx_sales_tx_digest ON sales(tx_digest);
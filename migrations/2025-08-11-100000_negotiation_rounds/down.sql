@@ -0,0 +1,6 @@
+ALTER TABLE offers DROP COLUMN IF EXISTS counter_value;
+ALTER TABLE offers DROP COLUMN IF EXISTS buyer_value;
+
+DROP INDEX IF EXISTS idx_negotiation_rounds_offer_id;
+
+DROP TABLE IF EXISTS negotiation_rounds;
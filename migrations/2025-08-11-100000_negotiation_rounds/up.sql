@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS negotiation_rounds (
+    id SERIAL PRIMARY KEY,
+    offer_id INTEGER NOT NULL REFERENCES offers(id),
+    round INTEGER NOT NULL,
+    domain_name VARCHAR NOT NULL,
+    proposer VARCHAR NOT NULL,
+    proposer_role ActivityRole NOT NULL,
+    action VARCHAR NOT NULL,
+    amount VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    checkpoint BIGINT NOT NULL,
+    tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_negotiation_rounds_offer_id ON negotiation_rounds(offer_id, round);
+
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS buyer_value VARCHAR;
+ALTER TABLE offers ADD COLUMN IF NOT EXISTS counter_value VARCHAR;
+
+-- `value` holds the owner's counter for countered offers, and the buyer's value otherwise
+UPDATE offers SET
+    buyer_value = CASE WHEN status = 'countered' THEN initial_value ELSE value END,
+    counter_value = CASE WHEN status IN ('countered', 'accepted-countered') THEN value END;
+
+ALTER TABLE offers ALTER COLUMN buyer_value SET NOT NULL;
+
+-- Best offers were taken from `value`, so countered offers counted with the owner's counter
+UPDATE domain_coin_stats d SET best_offer = (
+    SELECT MAX(buyer_value::NUMERIC)::VARCHAR FROM offers o
+    WHERE o.domain_name = d.domain_name AND o.coin_type = d.coin_type
+        AND o.status IN ('placed', 'countered')
+);
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_auctions_history_auction_id;
+DROP INDEX IF EXISTS idx_auctions_history_domain_name;
+DROP INDEX IF EXISTS idx_auctions_history_checkpoints;
+
+DROP TABLE IF EXISTS auctions_history;
+
+DROP INDEX IF EXISTS idx_offers_history_offer_id;
+DROP INDEX IF EXISTS idx_offers_history_domain_name;
+DROP INDEX IF EXISTS idx_offers_history_checkpoints;
+
+DROP TABLE IF EXISTS offers_history;
@@ -0,0 +1,62 @@
+-- Every state of offers and auctions, valid from a checkpoint (inclusive) until the next state
+-- replaces it (exclusive). The current state has no `valid_to_checkpoint`.
+CREATE TABLE IF NOT EXISTS offers_history (
+    history_id SERIAL PRIMARY KEY,
+    offer_id INTEGER NOT NULL REFERENCES offers(id),
+    domain_name VARCHAR NOT NULL,
+    buyer VARCHAR NOT NULL,
+    initial_value VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    owner VARCHAR,
+    status OfferStatus NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    last_tx_digest VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    stale_reason VARCHAR,
+    locked_value VARCHAR NOT NULL,
+    buyer_value VARCHAR NOT NULL,
+    counter_value VARCHAR,
+    valid_from_checkpoint BIGINT NOT NULL,
+    valid_to_checkpoint BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_offers_history_offer_id ON offers_history(offer_id, valid_from_checkpoint);
+CREATE INDEX IF NOT EXISTS idx_offers_history_domain_name ON offers_history(domain_name, valid_from_checkpoint);
+CREATE INDEX IF NOT EXISTS idx_offers_history_checkpoints ON offers_history(valid_from_checkpoint, valid_to_checkpoint);
+
+CREATE TABLE IF NOT EXISTS auctions_history (
+    history_id SERIAL PRIMARY KEY,
+    auction_id VARCHAR NOT NULL REFERENCES auctions(auction_id),
+    domain_name VARCHAR NOT NULL,
+    owner VARCHAR NOT NULL,
+    start_time BIGINT NOT NULL,
+    end_time BIGINT NOT NULL,
+    min_bid VARCHAR NOT NULL,
+    winner VARCHAR,
+    amount VARCHAR,
+    status AuctionStatus NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    last_tx_digest VARCHAR NOT NULL,
+    invalid_bid_count INTEGER NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    locked_value VARCHAR NOT NULL,
+    valid_from_checkpoint BIGINT NOT NULL,
+    valid_to_checkpoint BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_auctions_history_auction_id ON auctions_history(auction_id, valid_from_checkpoint);
+CREATE INDEX IF NOT EXISTS idx_auctions_history_domain_name ON auctions_history(domain_name, valid_from_checkpoint);
+CREATE INDEX IF NOT EXISTS idx_auctions_history_checkpoints ON auctions_history(valid_from_checkpoint, valid_to_checkpoint);
+
+-- The states indexed so far are known from the last checkpoint their pipeline committed
+INSERT INTO offers_history (offer_id, domain_name, buyer, initial_value, value, owner, status, updated_at, created_at, last_tx_digest, coin_type, stale_reason, locked_value, buyer_value, counter_value, valid_from_checkpoint)
+SELECT id, domain_name, buyer, initial_value, value, owner, status, updated_at, created_at, last_tx_digest, coin_type, stale_reason, locked_value, buyer_value, counter_value,
+    COALESCE((SELECT checkpoint_hi_inclusive FROM watermarks WHERE pipeline = 'offers'), 0)
+FROM offers;
+
+INSERT INTO auctions_history (auction_id, domain_name, owner, start_time, end_time, min_bid, winner, amount, status, updated_at, created_at, last_tx_digest, invalid_bid_count, coin_type, locked_value, valid_from_checkpoint)
+SELECT auction_id, domain_name, owner, start_time, end_time, min_bid, winner, amount, status, updated_at, created_at, last_tx_digest, invalid_bid_count, coin_type, locked_value,
+    COALESCE((SELECT checkpoint_hi_inclusive FROM watermarks WHERE pipeline = 'auctions'), 0)
+FROM auctions;
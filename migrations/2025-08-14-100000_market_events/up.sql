@@ -0,0 +1,17 @@
+-- Every marketplace event as committed by the pipelines, read by the live feed and webhooks
+CREATE TABLE IF NOT EXISTS market_events (
+    id BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR NOT NULL,
+    domain_name VARCHAR NOT NULL,
+    -- Parties of the event: buyers, owners, bidders, sellers and winners
+    addresses VARCHAR[] NOT NULL,
+    data JSONB NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    checkpoint BIGINT NOT NULL,
+    tx_digest VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_events_checkpoint ON market_events(checkpoint, id);
+CREATE INDEX IF NOT EXISTS idx_market_events_domain_name ON market_events(domain_name, checkpoint);
+CREATE INDEX IF NOT EXISTS idx_market_events_addresses ON market_events USING GIN(addresses);
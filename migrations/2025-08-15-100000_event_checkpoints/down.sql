@@ -0,0 +1,15 @@
+DROP INDEX IF EXISTS idx_offer_placed_checkpoint;
+DROP INDEX IF EXISTS idx_offer_cancelled_checkpoint;
+DROP INDEX IF EXISTS idx_offer_accepted_checkpoint;
+DROP INDEX IF EXISTS idx_offer_declined_checkpoint;
+DROP INDEX IF EXISTS idx_make_counter_offer_checkpoint;
+DROP INDEX IF EXISTS idx_accept_counter_offer_checkpoint;
+DROP INDEX IF EXISTS idx_bids_checkpoint;
+
+ALTER TABLE offer_placed DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE offer_cancelled DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE offer_accepted DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE offer_declined DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE make_counter_offer DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE accept_counter_offer DROP COLUMN IF EXISTS checkpoint;
+ALTER TABLE bids DROP COLUMN IF EXISTS checkpoint;
@@ -0,0 +1,35 @@
+-- Checkpoint of every raw event and bid, so they can be exported incrementally. Rows indexed
+-- before are filled from `transactions` where it has them, and left empty otherwise.
+ALTER TABLE offer_placed ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE offer_cancelled ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE offer_accepted ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE offer_declined ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE make_counter_offer ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE accept_counter_offer ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS checkpoint BIGINT;
+
+UPDATE offer_placed SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = offer_placed.tx_digest AND offer_placed.checkpoint IS NULL;
+UPDATE offer_cancelled SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = offer_cancelled.tx_digest AND offer_cancelled.checkpoint IS NULL;
+UPDATE offer_accepted SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = offer_accepted.tx_digest AND offer_accepted.checkpoint IS NULL;
+UPDATE offer_declined SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = offer_declined.tx_digest AND offer_declined.checkpoint IS NULL;
+UPDATE make_counter_offer SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = make_counter_offer.tx_digest AND make_counter_offer.checkpoint IS NULL;
+UPDATE accept_counter_offer SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = accept_counter_offer.tx_digest AND accept_counter_offer.checkpoint IS NULL;
+UPDATE bids SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = bids.tx_digest AND bids.checkpoint IS NULL;
+-- Sales backfilled from the events indexed before the sales table are filled the same way
+UPDATE sales SET checkpoint = transactions.checkpoint FROM transactions
+    WHERE transactions.tx_digest = sales.tx_digest AND sales.checkpoint IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_offer_placed_checkpoint ON offer_placed(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_offer_cancelled_checkpoint ON offer_cancelled(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_offer_accepted_checkpoint ON offer_accepted(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_offer_declined_checkpoint ON offer_declined(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_make_counter_offer_checkpoint ON make_counter_offer(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_accept_counter_offer_checkpoint ON accept_counter_offer(checkpoint);
+CREATE INDEX IF NOT EXISTS idx_bids_checkpoint ON bids(checkpoint);
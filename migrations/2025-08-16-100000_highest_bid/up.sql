@@ -0,0 +1,8 @@
+-- Highest valid bid of every auction, kept as bids arrive so validating a bid doesn't read the
+-- previous ones
+ALTER TABLE auctions ADD COLUMN IF NOT EXISTS highest_bid VARCHAR;
+
+UPDATE auctions SET highest_bid = (
+    SELECT MAX(amount::NUMERIC)::VARCHAR FROM bids
+    WHERE bids.auction_id = auctions.auction_id AND bids.is_valid
+);
@@ -0,0 +1 @@
+ALTER TABLE market_events DROP COLUMN IF EXISTS event_seq;
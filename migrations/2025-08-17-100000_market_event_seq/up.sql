@@ -0,0 +1,13 @@
+-- Index of the event among the events of its transaction, which together with the transaction
+-- digest identifies it across retried commits. Events published before are numbered in
+-- publication order.
+ALTER TABLE market_events ADD COLUMN IF NOT EXISTS event_seq BIGINT;
+
+UPDATE market_events SET event_seq = numbered.event_seq
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY tx_digest ORDER BY id) - 1 AS event_seq
+    FROM market_events
+) numbered
+WHERE numbered.id = market_events.id;
+
+ALTER TABLE market_events ALTER COLUMN event_seq SET NOT NULL;
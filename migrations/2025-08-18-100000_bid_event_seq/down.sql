@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_bids_tx_digest_event_seq;
+
+ALTER TABLE bids DROP COLUMN IF EXISTS event_seq;
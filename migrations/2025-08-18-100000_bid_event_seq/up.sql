@@ -0,0 +1,4 @@
+-- Index of the bid event among the events of its transaction. Bids indexed before have none.
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS event_seq BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_bids_tx_digest_event_seq ON bids(tx_digest, event_seq);
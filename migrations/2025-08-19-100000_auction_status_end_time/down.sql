@@ -0,0 +1 @@
+DROP INDEX IF EXISTS idx_auctions_status_end_time;
@@ -0,0 +1,2 @@
+-- Derived statuses are updated for every batch, by status and end time
+CREATE INDEX IF NOT EXISTS idx_auctions_status_end_time ON auctions(status, end_time);
@@ -0,0 +1 @@
+DROP VIEW IF EXISTS domains_compat;
@@ -0,0 +1,11 @@
+-- Domains with the SUI best offer and total volume they had before those moved to
+-- domain_coin_stats, for queries written against the old columns
+CREATE OR REPLACE VIEW domains_compat AS
+SELECT
+    d.*,
+    s.best_offer,
+    COALESCE(s.total_volume, '0') AS total_volume
+FROM domains d
+LEFT JOIN domain_coin_stats s
+    ON s.domain_name = d.domain_name
+    AND s.coin_type = '0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI';
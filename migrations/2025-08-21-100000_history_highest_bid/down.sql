@@ -0,0 +1 @@
+ALTER TABLE auctions_history DROP COLUMN IF EXISTS highest_bid;
@@ -0,0 +1,15 @@
+-- Highest valid bid of every state of an auction. Past states get the highest of the bids recorded
+-- up to the checkpoint they began at.
+ALTER TABLE auctions_history ADD COLUMN IF NOT EXISTS highest_bid VARCHAR;
+
+UPDATE auctions_history SET highest_bid = (
+    SELECT MAX(amount::NUMERIC)::VARCHAR FROM bids
+    WHERE bids.auction_id = auctions_history.auction_id AND bids.is_valid
+        AND bids.checkpoint <= auctions_history.valid_from_checkpoint
+);
+
+-- Bids indexed before checkpoints were recorded have none, but count in the current states
+UPDATE auctions_history SET highest_bid = auctions.highest_bid
+FROM auctions
+WHERE auctions.auction_id = auctions_history.auction_id
+    AND auctions_history.valid_to_checkpoint IS NULL;
@@ -0,0 +1,7 @@
+-- Last checkpoint every event sink received all the market events of, so sinks resume after a
+-- restart or a failure
+CREATE TABLE IF NOT EXISTS sink_cursors (
+    sink VARCHAR PRIMARY KEY,
+    checkpoint BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,188 @@
+use anyhow::Context;
+use log::info;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Words loaded from the configured wordlist, if any
+static WORDLIST: OnceLock<HashSet<String>> = OnceLock::new();
+
+const TOP_LEVEL_DOMAIN: &str = ".sui";
+const PUNYCODE_PREFIX: &str = "xn--";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Digits,
+    Letters,
+    Mixed,
+    Emoji,
+    Punycode,
+}
+
+impl CharClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CharClass::Digits => "digits",
+            CharClass::Letters => "letters",
+            CharClass::Mixed => "mixed",
+            CharClass::Emoji => "emoji",
+            CharClass::Punycode => "punycode",
+        }
+    }
+}
+
+/// Market segmentation tags of a domain name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCategory {
+    pub label_length: usize,
+    pub char_class: CharClass,
+    pub is_palindrome: bool,
+    pub has_repeating_pattern: bool,
+    pub is_subdomain: bool,
+    // Unknown when no wordlist is configured
+    pub is_dictionary_word: Option<bool>,
+}
+
+/// Loads a wordlist file with one word per line, used to tag dictionary names
+pub fn load_wordlist(path: &Path) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read wordlist {}", path.display()))?;
+
+    let words = parse_wordlist(&contents);
+
+    info!("Loaded {} words from {}", words.len(), path.display());
+
+    WORDLIST
+        .set(words)
+        .map_err(|_| anyhow::anyhow!("wordlist already loaded"))
+}
+
+fn parse_wordlist(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Tags a domain name, e.g. `abc.sui` or `sub.abc.sui`, based on its leftmost label
+pub fn categorize(domain_name: &str) -> NameCategory {
+    categorize_with(domain_name, WORDLIST.get())
+}
+
+fn categorize_with(domain_name: &str, wordlist: Option<&HashSet<String>>) -> NameCategory {
+    let name = domain_name
+        .strip_suffix(TOP_LEVEL_DOMAIN)
+        .unwrap_or(domain_name);
+    let is_subdomain = name.contains('.');
+    let label = name.split('.').next().unwrap_or_default();
+
+    let chars: Vec<char> = label.chars().collect();
+
+    NameCategory {
+        label_length: chars.len(),
+        char_class: char_class(label),
+        is_palindrome: chars.len() > 1 && chars.iter().eq(chars.iter().rev()),
+        has_repeating_pattern: has_repeating_pattern(&chars),
+        is_subdomain,
+        is_dictionary_word: wordlist.map(|words| words.contains(&label.to_lowercase())),
+    }
+}
+
+fn char_class(label: &str) -> CharClass {
+    if label.starts_with(PUNYCODE_PREFIX) {
+        CharClass::Punycode
+    } else if !label.is_ascii() {
+        // SuiNS labels are limited to ascii letters, digits and hyphens, apart from emoji
+        CharClass::Emoji
+    } else if !label.is_empty() && label.chars().all(|c| c.is_ascii_digit()) {
+        CharClass::Digits
+    } else if !label.is_empty() && label.chars().all(|c| c.is_ascii_alphabetic()) {
+        CharClass::Letters
+    } else {
+        CharClass::Mixed
+    }
+}
+
+// Whether the label is made of a shorter unit repeated, e.g. `aaa` or `abab`
+fn has_repeating_pattern(chars: &[char]) -> bool {
+    (1..=chars.len() / 2).any(|unit| {
+        chars.len() % unit == 0 && chars.chunks(unit).all(|chunk| chunk == &chars[..unit])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_classes() {
+        assert_eq!(char_class("123"), CharClass::Digits);
+        assert_eq!(char_class("abc"), CharClass::Letters);
+        assert_eq!(char_class("ABc"), CharClass::Letters);
+        assert_eq!(char_class("abc1"), CharClass::Mixed);
+        assert_eq!(char_class("a-b"), CharClass::Mixed);
+        assert_eq!(char_class(""), CharClass::Mixed);
+        assert_eq!(char_class("🔥"), CharClass::Emoji);
+        assert_eq!(char_class("xn--ls8h"), CharClass::Punycode);
+    }
+
+    #[test]
+    fn palindromes() {
+        assert!(categorize_with("abba.sui", None).is_palindrome);
+        assert!(categorize_with("12321.sui", None).is_palindrome);
+        assert!(!categorize_with("abc.sui", None).is_palindrome);
+        // A single character isn't considered a palindrome
+        assert!(!categorize_with("a.sui", None).is_palindrome);
+    }
+
+    #[test]
+    fn repeating_patterns() {
+        let chars = |label: &str| label.chars().collect::<Vec<_>>();
+
+        assert!(has_repeating_pattern(&chars("aaa")));
+        assert!(has_repeating_pattern(&chars("abab")));
+        assert!(has_repeating_pattern(&chars("123123")));
+        assert!(!has_repeating_pattern(&chars("aba")));
+        assert!(!has_repeating_pattern(&chars("ababa")));
+        assert!(!has_repeating_pattern(&chars("a")));
+    }
+
+    #[test]
+    fn subdomains() {
+        let category = categorize_with("sub.abc.sui", None);
+        assert!(category.is_subdomain);
+        assert_eq!(category.label_length, 3);
+        assert_eq!(category.char_class, CharClass::Letters);
+
+        let category = categorize_with("abcd.sui", None);
+        assert!(!category.is_subdomain);
+        assert_eq!(category.label_length, 4);
+    }
+
+    #[test]
+    fn label_length_counts_chars() {
+        assert_eq!(categorize_with("🔥🔥.sui", None).label_length, 2);
+    }
+
+    #[test]
+    fn wordlist_lookup() {
+        let wordlist = parse_wordlist("Apple\n  banana  \n\n");
+        assert_eq!(wordlist.len(), 2);
+
+        assert_eq!(
+            categorize_with("APPLE.sui", Some(&wordlist)).is_dictionary_word,
+            Some(true)
+        );
+        assert_eq!(
+            categorize_with("banana.abc.sui", Some(&wordlist)).is_dictionary_word,
+            Some(true)
+        );
+        assert_eq!(
+            categorize_with("cherry.sui", Some(&wordlist)).is_dictionary_word,
+            Some(false)
+        );
+        assert_eq!(categorize_with("apple.sui", None).is_dictionary_word, None);
+    }
+}
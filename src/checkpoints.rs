@@ -0,0 +1,190 @@
+use anyhow::{anyhow, bail, Context};
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use sui_indexer_alt_framework::Result;
+use url::Url;
+
+/// Extension of checkpoint files, named after their sequence number, e.g. `1234.chk`
+pub const CHECKPOINT_FILE_EXTENSION: &str = "chk";
+
+// First byte of checkpoint files, for checkpoints encoded as BCS
+const BCS_ENCODING: u8 = 1;
+
+/// Where to read checkpoints from when not indexing, in the format of the framework's ingestion
+#[derive(clap::Args, Debug, Clone)]
+pub struct CheckpointSource {
+    /// Directory of checkpoint files, e.g. recorded by the `record` command. The directory may skip
+    /// checkpoints, which only this reader supports
+    #[clap(long, conflicts_with = "remote_store_url")]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// Store to fetch checkpoint files from, e.g. `https://checkpoints.mainnet.sui.io`
+    #[clap(long)]
+    pub remote_store_url: Option<Url>,
+
+    /// First checkpoint to read. Required with `--remote-store-url`
+    #[clap(long)]
+    pub first_checkpoint: Option<u64>,
+
+    /// Last checkpoint to read, inclusive. Required with `--remote-store-url`
+    #[clap(long)]
+    pub last_checkpoint: Option<u64>,
+}
+
+impl CheckpointSource {
+    /// Sequence numbers of the checkpoints to read, in order. A directory may skip checkpoints,
+    /// e.g. those without marketplace events, so its files are listed rather than counted from the
+    /// first one as the framework's local ingestion does.
+    pub fn sequence_numbers(&self) -> Result<Vec<u64>> {
+        let first = self.first_checkpoint.unwrap_or(0);
+        let last = self.last_checkpoint.unwrap_or(u64::MAX);
+
+        if let Some(checkpoint_dir) = &self.checkpoint_dir {
+            let mut sequence_numbers = Vec::new();
+
+            let entries = fs::read_dir(checkpoint_dir)
+                .with_context(|| format!("failed to read {}", checkpoint_dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(CHECKPOINT_FILE_EXTENSION) {
+                    continue;
+                }
+
+                let Some(sequence_number) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                else {
+                    continue;
+                };
+
+                if (first..=last).contains(&sequence_number) {
+                    sequence_numbers.push(sequence_number);
+                }
+            }
+
+            sequence_numbers.sort_unstable();
+            return Ok(sequence_numbers);
+        }
+
+        if self.remote_store_url.is_none() {
+            bail!("Either --checkpoint-dir or --remote-store-url is required");
+        }
+        let (Some(first), Some(last)) = (self.first_checkpoint, self.last_checkpoint) else {
+            bail!("--first-checkpoint and --last-checkpoint are required with --remote-store-url");
+        };
+
+        Ok((first..=last).collect())
+    }
+
+    pub async fn read(
+        &self,
+        client: &reqwest::Client,
+        sequence_number: u64,
+    ) -> Result<CheckpointData> {
+        if let Some(checkpoint_dir) = &self.checkpoint_dir {
+            let path = file_path(checkpoint_dir, sequence_number);
+            let bytes =
+                fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            return decode(&bytes)
+                .with_context(|| format!("invalid checkpoint {}", path.display()));
+        }
+
+        let remote_store_url = self
+            .remote_store_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("no checkpoint source configured"))?;
+        let url = format!(
+            "{}/{}.{}",
+            remote_store_url.as_str().trim_end_matches('/'),
+            sequence_number,
+            CHECKPOINT_FILE_EXTENSION
+        );
+
+        let response = client.get(&url).send().await?;
+        if !response.status().is_success() {
+            bail!("Failed to fetch {}: {}", url, response.status());
+        }
+        let bytes = response.bytes().await?;
+
+        decode(&bytes).with_context(|| format!("invalid checkpoint {}", url))
+    }
+}
+
+/// Path of the file of a checkpoint in a checkpoint directory
+pub fn file_path(checkpoint_dir: &Path, sequence_number: u64) -> PathBuf {
+    checkpoint_dir.join(format!("{}.{}", sequence_number, CHECKPOINT_FILE_EXTENSION))
+}
+
+/// Reads the contents of a checkpoint file
+pub fn decode(bytes: &[u8]) -> Result<CheckpointData> {
+    match bytes.split_first() {
+        Some((&BCS_ENCODING, contents)) => Ok(bcs::from_bytes(contents)?),
+        Some((encoding, _)) => bail!("Unsupported checkpoint encoding {}", encoding),
+        None => bail!("Empty checkpoint file"),
+    }
+}
+
+/// Contents of the file of a checkpoint, as read by `decode` and the framework's ingestion
+pub fn encode(checkpoint: &CheckpointData) -> Result<Vec<u8>> {
+    let mut bytes = vec![BCS_ENCODING];
+    bytes.extend(bcs::to_bytes(checkpoint)?);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    fn checkpoint(sequence_number: u64) -> CheckpointData {
+        TestCheckpointDataBuilder::new(sequence_number)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction()
+            .build_checkpoint()
+    }
+
+    // Recorded directories skip the checkpoints without marketplace transactions
+    #[tokio::test]
+    async fn read_recorded_directory() -> anyhow::Result<()> {
+        let checkpoint_dir =
+            std::env::temp_dir().join(format!("recorded-checkpoints-{}", std::process::id()));
+        fs::create_dir_all(&checkpoint_dir)?;
+
+        let recorded = [checkpoint(3), checkpoint(7)];
+        for checkpoint in &recorded {
+            let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+            fs::write(
+                file_path(&checkpoint_dir, sequence_number),
+                encode(checkpoint)?,
+            )?;
+        }
+
+        let source = CheckpointSource {
+            checkpoint_dir: Some(checkpoint_dir.clone()),
+            remote_store_url: None,
+            first_checkpoint: None,
+            last_checkpoint: None,
+        };
+        let sequence_numbers = source.sequence_numbers()?;
+        assert_eq!(sequence_numbers, vec![3, 7]);
+
+        let client = reqwest::Client::new();
+        for (sequence_number, checkpoint) in sequence_numbers.into_iter().zip(&recorded) {
+            let read = source.read(&client, sequence_number).await?;
+            assert_eq!(read.checkpoint_summary.sequence_number, sequence_number);
+            assert_eq!(encode(&read)?, encode(checkpoint)?);
+        }
+
+        fs::remove_dir_all(&checkpoint_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_encoding() {
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[2, 0]).is_err());
+    }
+}
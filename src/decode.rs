@@ -0,0 +1,423 @@
+use crate::events::{
+    AcceptCounterOfferEvent, AuctionCancelledEvent, AuctionCreatedEvent, AuctionFinalizedEvent,
+    BidPlacedEvent, MakeCounterOfferEvent, OfferAcceptedEvent, OfferCancelledEvent,
+    OfferDeclinedEvent, OfferPlacedEvent,
+};
+use anyhow::{anyhow, bail, Context};
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sui_indexer_alt_framework::Result;
+
+/// Settings of the decoding of an event
+#[derive(clap::Args, Debug, Clone)]
+pub struct DecodeArgs {
+    /// Name of the event struct, e.g. `OfferPlacedEvent`, or its full type
+    pub event_type: String,
+
+    /// BCS contents of the event, as 0x prefixed hex, base64 or a list of bytes as logged, e.g.
+    /// `[12, 101]`
+    pub contents: String,
+
+    /// Encoding of the contents, guessed from them by default. Hex without the 0x prefix is read
+    /// as base64 unless this is `hex`
+    #[clap(long, value_enum)]
+    pub encoding: Option<Encoding>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    /// Comma-separated decimal bytes, optionally in brackets
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FieldKind {
+    Bytes,
+    Address,
+    U64,
+}
+
+// Fields of the event structs in `events.rs`, in the order BCS reads them. Only used to explain
+// failures, so keep it in sync with the structs.
+const LAYOUTS: [(&str, &[(&str, FieldKind)]); 10] = [
+    (
+        "OfferPlacedEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("address", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "OfferCancelledEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("address", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "OfferAcceptedEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("owner", FieldKind::Address),
+            ("buyer", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "OfferDeclinedEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("owner", FieldKind::Address),
+            ("buyer", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "MakeCounterOfferEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("owner", FieldKind::Address),
+            ("buyer", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "AcceptCounterOfferEvent",
+        &[
+            ("domain_name", FieldKind::Bytes),
+            ("buyer", FieldKind::Address),
+            ("value", FieldKind::U64),
+        ],
+    ),
+    (
+        "AuctionCreatedEvent",
+        &[
+            ("auction_id", FieldKind::Address),
+            ("domain_name", FieldKind::Bytes),
+            ("owner", FieldKind::Address),
+            ("start_time", FieldKind::U64),
+            ("end_time", FieldKind::U64),
+            ("min_bid", FieldKind::U64),
+        ],
+    ),
+    (
+        "BidPlacedEvent",
+        &[
+            ("auction_id", FieldKind::Address),
+            ("domain_name", FieldKind::Bytes),
+            ("bidder", FieldKind::Address),
+            ("amount", FieldKind::U64),
+        ],
+    ),
+    (
+        "AuctionFinalizedEvent",
+        &[
+            ("auction_id", FieldKind::Address),
+            ("domain_name", FieldKind::Bytes),
+            ("winner", FieldKind::Address),
+            ("amount", FieldKind::U64),
+        ],
+    ),
+    (
+        "AuctionCancelledEvent",
+        &[
+            ("auction_id", FieldKind::Address),
+            ("domain_name", FieldKind::Bytes),
+            ("owner", FieldKind::Address),
+        ],
+    ),
+];
+
+const ADDRESS_LENGTH: usize = 32;
+
+/// Decodes the contents of an event with its struct and prints it as JSON. When decoding fails,
+/// prints the fields read until then and the one decoding stopped at.
+pub fn run(args: &DecodeArgs) -> Result<()> {
+    let name = struct_name(&args.event_type);
+    let Some((_, layout)) = LAYOUTS.iter().find(|(event_name, _)| *event_name == name) else {
+        let names: Vec<&str> = LAYOUTS.iter().map(|(event_name, _)| *event_name).collect();
+        bail!(
+            "Unknown event type {}, expected one of {}",
+            name,
+            names.join(", ")
+        );
+    };
+
+    let bytes = parse_contents(&args.contents, args.encoding)?;
+
+    let decoded = match name {
+        "OfferPlacedEvent" => decode::<OfferPlacedEvent>(&bytes),
+        "OfferCancelledEvent" => decode::<OfferCancelledEvent>(&bytes),
+        "OfferAcceptedEvent" => decode::<OfferAcceptedEvent>(&bytes),
+        "OfferDeclinedEvent" => decode::<OfferDeclinedEvent>(&bytes),
+        "MakeCounterOfferEvent" => decode::<MakeCounterOfferEvent>(&bytes),
+        "AcceptCounterOfferEvent" => decode::<AcceptCounterOfferEvent>(&bytes),
+        "AuctionCreatedEvent" => decode::<AuctionCreatedEvent>(&bytes),
+        "BidPlacedEvent" => decode::<BidPlacedEvent>(&bytes),
+        "AuctionFinalizedEvent" => decode::<AuctionFinalizedEvent>(&bytes),
+        "AuctionCancelledEvent" => decode::<AuctionCancelledEvent>(&bytes),
+        _ => unreachable!("event types have a layout"),
+    };
+
+    match decoded {
+        Ok(event) => {
+            println!("{}", serde_json::to_string_pretty(&event)?);
+            Ok(())
+        }
+        Err(e) => {
+            println!(
+                "Failed to decode {} from {} bytes: {}",
+                name,
+                bytes.len(),
+                e
+            );
+            println!();
+            print!("{}", diagnose(layout, &bytes));
+            bail!("Failed to decode {}", name)
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned + Serialize>(bytes: &[u8]) -> Result<serde_json::Value> {
+    let event: T = bcs::from_bytes(bytes)?;
+    Ok(serde_json::to_value(event)?)
+}
+
+// `0x..::offer::OfferPlacedEvent<0x2::sui::SUI>` is named `OfferPlacedEvent`
+fn struct_name(event_type: &str) -> &str {
+    let without_params = event_type.split('<').next().unwrap_or(event_type);
+    without_params
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_params)
+        .trim()
+}
+
+fn parse_contents(contents: &str, encoding: Option<Encoding>) -> Result<Vec<u8>> {
+    let contents = contents.trim();
+
+    // Hex digits are valid base64 too, so only prefixed hex is told apart
+    let encoding = encoding.unwrap_or_else(|| {
+        if contents.starts_with('[') || contents.contains(',') {
+            Encoding::Bytes
+        } else if contents.starts_with("0x") {
+            Encoding::Hex
+        } else {
+            Encoding::Base64
+        }
+    });
+
+    match encoding {
+        Encoding::Hex => {
+            let digits = contents.strip_prefix("0x").unwrap_or(contents);
+            hex::decode(digits).context("invalid hex contents")
+        }
+        Encoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(contents)
+            .context("invalid base64 contents"),
+        Encoding::Bytes => contents
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(str::trim)
+            .filter(|byte| !byte.is_empty())
+            .map(|byte| {
+                byte.parse::<u8>()
+                    .with_context(|| format!("invalid byte {:?}", byte))
+            })
+            .collect(),
+    }
+}
+
+// One line per field with its offset and value, up to the field decoding stopped at, then any
+// bytes left after the last field
+fn diagnose(layout: &[(&str, FieldKind)], bytes: &[u8]) -> String {
+    let mut report = String::new();
+    let mut offset = 0;
+
+    for (field, kind) in layout {
+        let type_name = match kind {
+            FieldKind::Bytes => "vector<u8>",
+            FieldKind::Address => "address",
+            FieldKind::U64 => "u64",
+        };
+
+        match read_field(*kind, &bytes[offset..]) {
+            Ok((value, length)) => {
+                report.push_str(&format!(
+                    "{:>6}  {:<12} {:<11} {}\n",
+                    offset, field, type_name, value
+                ));
+                offset += length;
+            }
+            Err(e) => {
+                report.push_str(&format!(
+                    "{:>6}  {:<12} {:<11} decoding stopped: {}\n",
+                    offset, field, type_name, e
+                ));
+                return report;
+            }
+        }
+    }
+
+    if offset < bytes.len() {
+        report.push_str(&format!(
+            "{:>6}  {} trailing bytes: {}\n",
+            offset,
+            bytes.len() - offset,
+            hex::encode(&bytes[offset..])
+        ));
+    }
+
+    report
+}
+
+// Returns the field as text and the number of bytes it takes
+fn read_field(kind: FieldKind, bytes: &[u8]) -> Result<(String, usize)> {
+    match kind {
+        FieldKind::Bytes => {
+            let (length, prefix) = read_uleb128(bytes)?;
+            let end = prefix + length;
+            if bytes.len() < end {
+                bail!(
+                    "length prefix is {} bytes but only {} are left",
+                    length,
+                    bytes.len() - prefix
+                );
+            }
+
+            let contents = &bytes[prefix..end];
+            let value = match std::str::from_utf8(contents) {
+                Ok(text) => format!("{:?} ({} bytes)", text, length),
+                Err(_) => format!("0x{} ({} bytes, not UTF-8)", hex::encode(contents), length),
+            };
+            Ok((value, end))
+        }
+        FieldKind::Address => {
+            let Some(address) = bytes.get(..ADDRESS_LENGTH) else {
+                bail!(
+                    "needs {} bytes but only {} are left",
+                    ADDRESS_LENGTH,
+                    bytes.len()
+                );
+            };
+            Ok((format!("0x{}", hex::encode(address)), ADDRESS_LENGTH))
+        }
+        FieldKind::U64 => {
+            let Some(value) = bytes.get(..8) else {
+                bail!("needs 8 bytes but only {} are left", bytes.len());
+            };
+            let value = u64::from_le_bytes(value.try_into()?);
+            Ok((value.to_string(), 8))
+        }
+    }
+}
+
+// Lengths of vectors are ULEB128 encoded, on at most 5 bytes for u32 values
+fn read_uleb128(bytes: &[u8]) -> Result<(usize, usize)> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = u32::try_from(value)
+                .map_err(|_| anyhow!("length prefix {} is larger than a u32", value))?;
+            return Ok((value as usize, i + 1));
+        }
+    }
+
+    if bytes.len() < 5 {
+        bail!("length prefix is cut off after {} bytes", bytes.len());
+    }
+    bail!("length prefix is longer than 5 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(parse_contents("0x0102ff", None).unwrap(), vec![1, 2, 255]);
+        assert_eq!(
+            parse_contents("0102ff", Some(Encoding::Hex)).unwrap(),
+            vec![1, 2, 255]
+        );
+    }
+
+    #[test]
+    fn parse_base64() {
+        assert_eq!(parse_contents("AQL/", None).unwrap(), vec![1, 2, 255]);
+    }
+
+    #[test]
+    fn parse_bytes() {
+        assert_eq!(
+            parse_contents("[1, 2, 255]", None).unwrap(),
+            vec![1, 2, 255]
+        );
+        assert_eq!(parse_contents("1,2,255", None).unwrap(), vec![1, 2, 255]);
+        assert!(parse_contents("[1, 256]", None).is_err());
+    }
+
+    // Hex digits without the prefix are also valid base64
+    #[test]
+    fn parse_unprefixed_hex_as_base64() {
+        assert_eq!(
+            parse_contents("abcd", None).unwrap(),
+            vec![0x69, 0xb7, 0x1d]
+        );
+        assert_eq!(
+            parse_contents("abcd", Some(Encoding::Hex)).unwrap(),
+            vec![0xab, 0xcd]
+        );
+    }
+
+    #[test]
+    fn uleb128() {
+        assert_eq!(read_uleb128(&[0x05]).unwrap(), (5, 1));
+        assert_eq!(read_uleb128(&[0x80, 0x01, 0xaa]).unwrap(), (128, 2));
+        assert_eq!(read_uleb128(&[0xff, 0xff, 0x03]).unwrap(), (65_535, 3));
+        assert_eq!(
+            read_uleb128(&[0xff, 0xff, 0xff, 0xff, 0x0f]).unwrap(),
+            (u32::MAX as usize, 5)
+        );
+    }
+
+    #[test]
+    fn truncated_uleb128() {
+        assert!(read_uleb128(&[]).is_err());
+        assert!(read_uleb128(&[0x80, 0x80]).is_err());
+        assert!(read_uleb128(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]).is_err());
+    }
+
+    #[test]
+    fn diagnose_truncated_event() {
+        let (_, layout) = LAYOUTS
+            .iter()
+            .find(|(name, _)| *name == "OfferPlacedEvent")
+            .unwrap();
+
+        // The value is cut off after 4 of its 8 bytes
+        let mut bytes = vec![2, b'a', b'b'];
+        bytes.extend([1; ADDRESS_LENGTH]);
+        bytes.extend([0; 4]);
+
+        let report = diagnose(layout, &bytes);
+        let address = format!("0x{}", "01".repeat(ADDRESS_LENGTH));
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            vec![
+                "     0  domain_name  vector<u8>  \"ab\" (2 bytes)".to_string(),
+                format!("     3  address      address     {}", address),
+                "    35  value        u64         decoding stopped: needs 8 bytes but only 4 are left"
+                    .to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::checkpoints::CheckpointSource;
+use crate::handlers::auctions_handler::AuctionsHandlerPipeline;
+use crate::handlers::name_records_handler::NameRecordsHandlerPipeline;
+use crate::handlers::offer_events_handler::OfferEventsHandlerPipeline;
+use crate::handlers::offers_handler::OffersHandlerPipeline;
+use crate::handlers::registration_owners_handler::RegistrationOwnersHandlerPipeline;
+use crate::handlers::transactions_handler::TransactionsHandlerPipeline;
+use log::info;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use sui_indexer_alt_framework::Result;
+
+/// Settings of a dry run
+#[derive(clap::Args, Debug, Clone)]
+pub struct DryRunArgs {
+    #[clap(flatten)]
+    pub source: CheckpointSource,
+
+    /// Pipelines to run, separated by commas. All of them by default
+    #[clap(long, value_delimiter = ',')]
+    pub pipelines: Vec<String>,
+}
+
+/// A value produced by a pipeline, as printed by the dry run: the decoded event and, for
+/// sequential pipelines, the change their commit would apply to the tables
+pub trait DryRunValue {
+    /// `None` for the values every checkpoint produces, e.g. to advance derived statuses
+    fn to_json(&self) -> Option<Value>;
+}
+
+/// Runs the processors of the pipelines over the checkpoints of the source and prints every value
+/// they produce as a JSON line on stdout. Nothing is read from or written to the database, so
+/// changes depending on the indexed state, like bid validation, derived auction statuses and
+/// stale offers, are left out.
+pub async fn run(
+    args: &DryRunArgs,
+    contract_package_id: &str,
+    suins_package_id: Option<&str>,
+) -> Result<()> {
+    let offer_events = OfferEventsHandlerPipeline::new(contract_package_id.to_string());
+    let transactions = TransactionsHandlerPipeline::new(contract_package_id.to_string());
+    let offers = OffersHandlerPipeline::new(contract_package_id.to_string());
+    let auctions = AuctionsHandlerPipeline::new(contract_package_id.to_string());
+    let name_records = suins_package_id.map(|id| NameRecordsHandlerPipeline::new(id.to_string()));
+    let registration_owners =
+        suins_package_id.map(|id| RegistrationOwnersHandlerPipeline::new(id.to_string()));
+
+    let client = reqwest::Client::new();
+    let sequence_numbers = args.source.sequence_numbers()?;
+    info!("Dry run over {} checkpoints", sequence_numbers.len());
+
+    let mut out = io::stdout().lock();
+
+    for sequence_number in sequence_numbers {
+        let checkpoint = Arc::new(args.source.read(&client, sequence_number).await?);
+
+        process(&offer_events, &checkpoint, args, &mut out)?;
+        process(&transactions, &checkpoint, args, &mut out)?;
+        process(&offers, &checkpoint, args, &mut out)?;
+        process(&auctions, &checkpoint, args, &mut out)?;
+        if let Some(name_records) = &name_records {
+            process(name_records, &checkpoint, args, &mut out)?;
+        }
+        if let Some(registration_owners) = &registration_owners {
+            process(registration_owners, &checkpoint, args, &mut out)?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn process<P>(
+    pipeline: &P,
+    checkpoint: &Arc<CheckpointData>,
+    args: &DryRunArgs,
+    out: &mut dyn Write,
+) -> Result<()>
+where
+    P: Processor,
+    P::Value: DryRunValue,
+{
+    if !args.pipelines.is_empty() && !args.pipelines.iter().any(|name| name == P::NAME) {
+        return Ok(());
+    }
+
+    for value in pipeline.process(checkpoint)? {
+        let Some(Value::Object(fields)) = value.to_json() else {
+            continue;
+        };
+
+        let mut line = serde_json::json!({
+            "pipeline": P::NAME,
+            "checkpoint": checkpoint.checkpoint_summary.sequence_number,
+        });
+        if let Some(line) = line.as_object_mut() {
+            line.extend(fields);
+        }
+
+        writeln!(out, "{}", line)?;
+    }
+
+    Ok(())
+}
@@ -1,30 +1,39 @@
 use log::error;
 use serde::Deserialize;
+use sui_types::event::Event;
 
-#[derive(serde::Deserialize, Debug, Clone)]
+// Amounts of events without a coin type parameter are denominated in SUI
+pub const SUI_COIN_TYPE: &str =
+    "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI";
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct OfferPlacedEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub address: sui_types::base_types::SuiAddress,
     pub value: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct OfferCancelledEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub address: sui_types::base_types::SuiAddress,
     pub value: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct OfferAcceptedEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub owner: sui_types::base_types::SuiAddress,
     pub buyer: sui_types::base_types::SuiAddress,
     pub value: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct OfferDeclinedEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub owner: sui_types::base_types::SuiAddress,
     pub buyer: sui_types::base_types::SuiAddress,
@@ -32,8 +41,9 @@ pub struct OfferDeclinedEvent {
 }
 
 // owner can create a counter offer
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct MakeCounterOfferEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub owner: sui_types::base_types::SuiAddress,
     pub buyer: sui_types::base_types::SuiAddress,
@@ -41,16 +51,18 @@ pub struct MakeCounterOfferEvent {
 }
 
 // buyer can accept counter offer
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct AcceptCounterOfferEvent {
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub buyer: sui_types::base_types::SuiAddress,
     pub value: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct AuctionCreatedEvent {
     pub auction_id: sui_types::base_types::ObjectID,
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub owner: sui_types::base_types::SuiAddress,
     pub start_time: u64,
@@ -58,25 +70,28 @@ pub struct AuctionCreatedEvent {
     pub min_bid: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct BidPlacedEvent {
     pub auction_id: sui_types::base_types::ObjectID,
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub bidder: sui_types::base_types::SuiAddress,
     pub amount: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct AuctionFinalizedEvent {
     pub auction_id: sui_types::base_types::ObjectID,
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub winner: sui_types::base_types::SuiAddress,
     pub amount: u64,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct AuctionCancelledEvent {
     pub auction_id: sui_types::base_types::ObjectID,
+    #[serde(serialize_with = "serialize_domain_name")]
     pub domain_name: Vec<u8>,
     pub owner: sui_types::base_types::SuiAddress,
 }
@@ -98,4 +113,79 @@ pub fn try_deserialize_event<T: for<'a> Deserialize<'a>>(
 
 pub fn convert_domain_name(domain_name: &[u8]) -> String {
     String::from_utf8_lossy(domain_name).to_string()
-}
\ No newline at end of file
+}
+
+// Domain names are written as text rather than bytes, e.g. in the JSON of the dry run
+fn serialize_domain_name<S: serde::Serializer>(
+    domain_name: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&convert_domain_name(domain_name))
+}
+
+/// Name of the event struct, without its address, module or type parameters
+pub fn event_name(event: &Event) -> &str {
+    event.type_.name.as_str()
+}
+
+/// Coin type of an event, taken from its type parameters, e.g. `OfferPlacedEvent<T>`
+pub fn event_coin_type(event: &Event) -> String {
+    match event.type_.type_params.first() {
+        Some(coin_type) => coin_type.to_canonical_string(/* with_prefix */ true),
+        None => SUI_COIN_TYPE.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::StructTag;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+
+    fn event(type_: &str) -> Event {
+        let type_: StructTag = type_.parse().unwrap();
+        Event {
+            package_id: ObjectID::from(type_.address),
+            transaction_module: type_.module.clone(),
+            sender: SuiAddress::ZERO,
+            type_,
+            contents: vec![],
+        }
+    }
+
+    #[test]
+    fn sui_by_default() {
+        let event = event("0xabc::offer::OfferPlacedEvent");
+
+        assert_eq!(event_name(&event), "OfferPlacedEvent");
+        assert_eq!(event_coin_type(&event), SUI_COIN_TYPE);
+    }
+
+    #[test]
+    fn sui_type_parameter() {
+        let event = event("0xabc::offer::OfferPlacedEvent<0x2::sui::SUI>");
+
+        assert_eq!(event_name(&event), "OfferPlacedEvent");
+        assert_eq!(event_coin_type(&event), SUI_COIN_TYPE);
+    }
+
+    #[test]
+    fn coin_type_parameter() {
+        let event = event("0xabc::auction::BidPlacedEvent<0xdef::usdc::USDC>");
+
+        assert_eq!(event_name(&event), "BidPlacedEvent");
+        assert_eq!(
+            event_coin_type(&event),
+            "0x0000000000000000000000000000000000000000000000000000000000000def::usdc::USDC"
+        );
+    }
+
+    // A type parameter that isn't a coin is kept as it is, so its amounts aren't counted as SUI
+    #[test]
+    fn malformed_coin_type() {
+        let event = event("0xabc::offer::OfferPlacedEvent<u64>");
+
+        assert_eq!(event_name(&event), "OfferPlacedEvent");
+        assert_eq!(event_coin_type(&event), "u64");
+    }
+}
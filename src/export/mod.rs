@@ -0,0 +1,509 @@
+use crate::models::{
+    AcceptCounterOffer, AuctionHistory, Bid, MakeCounterOffer, OfferAccepted, OfferCancelled,
+    OfferDeclined, OfferHistory, OfferPlaced, Sale,
+};
+use crate::schema::{
+    accept_counter_offer, auctions_history, bids, make_counter_offer, offer_accepted,
+    offer_cancelled, offer_declined, offer_placed, offers_history, sales, watermarks,
+};
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+pub mod parquet;
+pub mod query;
+
+use self::parquet::ToRecordBatch;
+use self::query::ExportFilter;
+
+// Last checkpoint exported of a dataset, kept next to its files
+const CURSOR_FILE: &str = "_last_checkpoint";
+
+// Partition of the rows indexed before checkpoints were recorded
+const NO_CHECKPOINT_PARTITION: &str = "checkpoint=none";
+
+/// Tables that can be exported, each to its own directory
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[value(rename_all = "snake_case")]
+pub enum Dataset {
+    OfferPlaced,
+    OfferCancelled,
+    OfferAccepted,
+    OfferDeclined,
+    MakeCounterOffer,
+    AcceptCounterOffer,
+    Bids,
+    Sales,
+    OffersHistory,
+    AuctionsHistory,
+    // Only exported as csv or json, for lack of checkpoints to export them incrementally
+    Offers,
+    Auctions,
+    MarketEvents,
+}
+
+impl Dataset {
+    /// Datasets exported to Parquet when none is selected
+    pub const PARQUET: [Dataset; 10] = [
+        Dataset::OfferPlaced,
+        Dataset::OfferCancelled,
+        Dataset::OfferAccepted,
+        Dataset::OfferDeclined,
+        Dataset::MakeCounterOffer,
+        Dataset::AcceptCounterOffer,
+        Dataset::Bids,
+        Dataset::Sales,
+        Dataset::OffersHistory,
+        Dataset::AuctionsHistory,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dataset::OfferPlaced => "offer_placed",
+            Dataset::OfferCancelled => "offer_cancelled",
+            Dataset::OfferAccepted => "offer_accepted",
+            Dataset::OfferDeclined => "offer_declined",
+            Dataset::MakeCounterOffer => "make_counter_offer",
+            Dataset::AcceptCounterOffer => "accept_counter_offer",
+            Dataset::Bids => "bids",
+            Dataset::Sales => "sales",
+            Dataset::OffersHistory => "offers_history",
+            Dataset::AuctionsHistory => "auctions_history",
+            Dataset::Offers => "offers",
+            Dataset::Auctions => "auctions",
+            Dataset::MarketEvents => "market_events",
+        }
+    }
+
+    // Pipelines writing the dataset. Only checkpoints committed by all of them that are running
+    // are exported, so a later run never finds rows behind the cursor.
+    fn pipelines(&self) -> &'static [&'static str] {
+        match self {
+            Dataset::OfferPlaced
+            | Dataset::OfferCancelled
+            | Dataset::OfferAccepted
+            | Dataset::OfferDeclined
+            | Dataset::MakeCounterOffer
+            | Dataset::AcceptCounterOffer => &["offer_events"],
+            Dataset::Bids | Dataset::Auctions | Dataset::AuctionsHistory => &["auctions"],
+            Dataset::Sales | Dataset::MarketEvents => &["offers", "auctions"],
+            // Offers go stale from the pipelines following sales, transfers and expirations
+            Dataset::Offers | Dataset::OffersHistory => {
+                &["offers", "auctions", "name_records", "registration_owners"]
+            }
+        }
+    }
+}
+
+/// Format of the exported rows
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Typed columns in files partitioned by day or checkpoint, exported incrementally
+    Parquet,
+    /// Rows of a single dataset matching the filters, with a header line
+    Csv,
+    /// Rows of a single dataset matching the filters, as an array of objects
+    Json,
+}
+
+/// How exported files are grouped into directories
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partitioning {
+    /// `date=YYYY-MM-DD`, by the time of the event or state change
+    Day,
+    /// `checkpoints=<first>-<last>`, `--checkpoints-per-partition` wide
+    Checkpoint,
+}
+
+/// Settings of the export of the indexed tables
+#[derive(clap::Args, Debug, Clone)]
+pub struct ExportArgs {
+    #[clap(long, value_enum, default_value_t = Format::Parquet)]
+    pub format: Format,
+
+    /// Directory to write Parquet files to, with a sub directory per dataset, or file to write csv
+    /// or json to instead of stdout
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+
+    /// Datasets to export, separated by commas. All of them by default for Parquet, and exactly
+    /// one for csv and json
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub datasets: Vec<Dataset>,
+
+    #[clap(flatten)]
+    pub filter: ExportFilter,
+
+    #[clap(long, value_enum, default_value_t = Partitioning::Day)]
+    pub partition: Partitioning,
+
+    #[clap(long, default_value_t = 1_000_000, value_parser = clap::value_parser!(i64).range(1..))]
+    pub checkpoints_per_partition: i64,
+
+    /// First checkpoint to export, instead of resuming after the last exported one
+    #[clap(long)]
+    pub from_checkpoint: Option<i64>,
+
+    /// Checkpoints read from the database at once
+    #[clap(long, default_value_t = 100_000, value_parser = clap::value_parser!(i64).range(1..))]
+    pub batch_checkpoints: i64,
+}
+
+/// A row of an exported dataset
+#[async_trait]
+pub trait ExportRow: ToRecordBatch + Sized + Send {
+    /// Rows of the dataset in the checkpoint range, inclusive, in checkpoint order
+    async fn load<'a>(conn: &mut Connection<'a>, from: i64, to: i64) -> Result<Vec<Self>>;
+
+    /// Rows of the dataset indexed before checkpoints were recorded, which have none
+    async fn load_without_checkpoint<'a>(_conn: &mut Connection<'a>) -> Result<Vec<Self>> {
+        Ok(vec![])
+    }
+
+    fn checkpoint(&self) -> i64;
+
+    /// Time the row is filed under when partitioning by day
+    fn timestamp(&self) -> DateTime<Utc>;
+}
+
+// Raw event tables only differ by their model
+macro_rules! raw_event_row {
+    ($model:ty, $table:ident) => {
+        #[async_trait]
+        impl ExportRow for $model {
+            async fn load<'a>(conn: &mut Connection<'a>, from: i64, to: i64) -> Result<Vec<Self>> {
+                let rows = $table::table
+                    .filter($table::checkpoint.between(from, to))
+                    .order(($table::checkpoint.asc(), $table::id.asc()))
+                    .select(<$model>::as_select())
+                    .load(conn)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn load_without_checkpoint<'a>(conn: &mut Connection<'a>) -> Result<Vec<Self>> {
+                let rows = $table::table
+                    .filter($table::checkpoint.is_null())
+                    .order($table::id.asc())
+                    .select(<$model>::as_select())
+                    .load(conn)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            // Only rows with a checkpoint are partitioned by it
+            fn checkpoint(&self) -> i64 {
+                self.checkpoint.unwrap_or_default()
+            }
+
+            fn timestamp(&self) -> DateTime<Utc> {
+                self.created_at
+            }
+        }
+    };
+}
+
+raw_event_row!(OfferPlaced, offer_placed);
+raw_event_row!(OfferCancelled, offer_cancelled);
+raw_event_row!(OfferAccepted, offer_accepted);
+raw_event_row!(OfferDeclined, offer_declined);
+raw_event_row!(MakeCounterOffer, make_counter_offer);
+raw_event_row!(AcceptCounterOffer, accept_counter_offer);
+raw_event_row!(Bid, bids);
+
+#[async_trait]
+impl ExportRow for Sale {
+    async fn load<'a>(conn: &mut Connection<'a>, from: i64, to: i64) -> Result<Vec<Self>> {
+        let rows = sales::table
+            .filter(sales::checkpoint.between(from, to))
+            .order((sales::checkpoint.asc(), sales::id.asc()))
+            .select(Sale::as_select())
+            .load(conn)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn load_without_checkpoint<'a>(conn: &mut Connection<'a>) -> Result<Vec<Self>> {
+        let rows = sales::table
+            .filter(sales::checkpoint.is_null())
+            .order(sales::id.asc())
+            .select(Sale::as_select())
+            .load(conn)
+            .await?;
+
+        Ok(rows)
+    }
+
+    // Only rows with a checkpoint are partitioned by it
+    fn checkpoint(&self) -> i64 {
+        self.checkpoint.unwrap_or_default()
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+// States are exported as they begin. The end of the latest states is only known later, so
+// `valid_to_checkpoint` is best taken from the states following them in later exports.
+#[async_trait]
+impl ExportRow for OfferHistory {
+    async fn load<'a>(conn: &mut Connection<'a>, from: i64, to: i64) -> Result<Vec<Self>> {
+        let rows = offers_history::table
+            .filter(offers_history::valid_from_checkpoint.between(from, to))
+            .order((
+                offers_history::valid_from_checkpoint.asc(),
+                offers_history::history_id.asc(),
+            ))
+            .select(OfferHistory::as_select())
+            .load(conn)
+            .await?;
+
+        Ok(rows)
+    }
+
+    fn checkpoint(&self) -> i64 {
+        self.valid_from_checkpoint
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+#[async_trait]
+impl ExportRow for AuctionHistory {
+    async fn load<'a>(conn: &mut Connection<'a>, from: i64, to: i64) -> Result<Vec<Self>> {
+        let rows = auctions_history::table
+            .filter(auctions_history::valid_from_checkpoint.between(from, to))
+            .order((
+                auctions_history::valid_from_checkpoint.asc(),
+                auctions_history::history_id.asc(),
+            ))
+            .select(AuctionHistory::as_select())
+            .load(conn)
+            .await?;
+
+        Ok(rows)
+    }
+
+    fn checkpoint(&self) -> i64 {
+        self.valid_from_checkpoint
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+/// Exports the selected datasets in the requested format
+pub async fn run<'a>(conn: &mut Connection<'a>, args: &ExportArgs) -> Result<()> {
+    match args.format {
+        Format::Parquet => export_parquet(conn, args).await,
+        Format::Csv | Format::Json => query::run(conn, args).await,
+    }
+}
+
+// Exports each dataset from the checkpoint after its last export up to the last checkpoint
+// committed by the pipelines writing it
+async fn export_parquet<'a>(conn: &mut Connection<'a>, args: &ExportArgs) -> Result<()> {
+    let Some(output) = &args.output else {
+        bail!("--output is required for Parquet exports");
+    };
+    if !args.filter.is_empty() {
+        bail!("Filters only apply to csv and json exports");
+    }
+
+    let datasets = if args.datasets.is_empty() {
+        Dataset::PARQUET.to_vec()
+    } else {
+        args.datasets.clone()
+    };
+
+    for dataset in datasets {
+        match dataset {
+            Dataset::OfferPlaced => export::<OfferPlaced>(conn, output, dataset, args).await?,
+            Dataset::OfferCancelled => {
+                export::<OfferCancelled>(conn, output, dataset, args).await?
+            }
+            Dataset::OfferAccepted => export::<OfferAccepted>(conn, output, dataset, args).await?,
+            Dataset::OfferDeclined => export::<OfferDeclined>(conn, output, dataset, args).await?,
+            Dataset::MakeCounterOffer => {
+                export::<MakeCounterOffer>(conn, output, dataset, args).await?
+            }
+            Dataset::AcceptCounterOffer => {
+                export::<AcceptCounterOffer>(conn, output, dataset, args).await?
+            }
+            Dataset::Bids => export::<Bid>(conn, output, dataset, args).await?,
+            Dataset::Sales => export::<Sale>(conn, output, dataset, args).await?,
+            Dataset::OffersHistory => export::<OfferHistory>(conn, output, dataset, args).await?,
+            Dataset::AuctionsHistory => {
+                export::<AuctionHistory>(conn, output, dataset, args).await?
+            }
+            Dataset::Offers | Dataset::Auctions | Dataset::MarketEvents => {
+                bail!("{} can only be exported as csv or json", dataset.name())
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn export<'a, T: ExportRow>(
+    conn: &mut Connection<'a>,
+    output: &Path,
+    dataset: Dataset,
+    args: &ExportArgs,
+) -> Result<()> {
+    let directory = output.join(dataset.name());
+    fs::create_dir_all(&directory)
+        .with_context(|| format!("failed to create {}", directory.display()))?;
+
+    let from = match args.from_checkpoint {
+        Some(from_checkpoint) => from_checkpoint,
+        None => read_cursor(&directory)?.map_or(0, |checkpoint| checkpoint + 1),
+    };
+
+    let Some(to) = committed_checkpoint(conn, dataset.pipelines()).await? else {
+        warn!(
+            "Skipping export of {}: its pipelines haven't committed anything",
+            dataset.name()
+        );
+        return Ok(());
+    };
+
+    if to < from {
+        info!(
+            "Nothing to export for {} after checkpoint {}",
+            dataset.name(),
+            to
+        );
+        return Ok(());
+    }
+
+    info!(
+        "Exporting {} from checkpoint {} to {}",
+        dataset.name(),
+        from,
+        to
+    );
+
+    // Rows indexed before checkpoints were recorded are older than any with a checkpoint, so they
+    // are exported with the first checkpoints, to a partition of their own
+    if from == 0 {
+        let rows = T::load_without_checkpoint(conn).await?;
+        if !rows.is_empty() {
+            let partition_directory = directory.join(NO_CHECKPOINT_PARTITION);
+            fs::create_dir_all(&partition_directory)
+                .with_context(|| format!("failed to create {}", partition_directory.display()))?;
+
+            let path = partition_directory.join("part-without-checkpoint.parquet");
+            parquet::write(&path, &T::record_batch(&rows)?)?;
+
+            info!(
+                "Exported {} rows of {} without a checkpoint",
+                rows.len(),
+                dataset.name()
+            );
+        }
+    }
+
+    let mut lo = from;
+    while lo <= to {
+        let hi = to.min(lo.saturating_add(args.batch_checkpoints - 1));
+
+        let rows = T::load(conn, lo, hi).await?;
+        let count = rows.len();
+
+        for (partition, rows) in partitions(rows, args) {
+            let partition_directory = directory.join(partition);
+            fs::create_dir_all(&partition_directory)
+                .with_context(|| format!("failed to create {}", partition_directory.display()))?;
+
+            let path = partition_directory.join(format!("part-{:012}-{:012}.parquet", lo, hi));
+            parquet::write(&path, &T::record_batch(&rows)?)?;
+        }
+
+        write_cursor(&directory, hi)?;
+        info!(
+            "Exported {} rows of {} up to checkpoint {}",
+            count,
+            dataset.name(),
+            hi
+        );
+
+        lo = hi + 1;
+    }
+
+    Ok(())
+}
+
+// Groups rows by the directory of their partition, keeping their order
+fn partitions<T: ExportRow>(rows: Vec<T>, args: &ExportArgs) -> BTreeMap<String, Vec<T>> {
+    let mut partitions: BTreeMap<String, Vec<T>> = BTreeMap::new();
+
+    for row in rows {
+        let partition = match args.partition {
+            Partitioning::Day => format!("date={}", row.timestamp().format("%Y-%m-%d")),
+            Partitioning::Checkpoint => {
+                let width = args.checkpoints_per_partition;
+                let first = row.checkpoint() / width * width;
+                format!("checkpoints={}-{}", first, first + width - 1)
+            }
+        };
+
+        partitions.entry(partition).or_default().push(row);
+    }
+
+    partitions
+}
+
+// Lowest checkpoint committed by the running pipelines among the given ones
+async fn committed_checkpoint<'a>(
+    conn: &mut Connection<'a>,
+    pipelines: &[&str],
+) -> Result<Option<i64>> {
+    let checkpoint = watermarks::table
+        .filter(watermarks::pipeline.eq_any(pipelines.to_vec()))
+        .select(diesel::dsl::min(watermarks::checkpoint_hi_inclusive))
+        .first(conn)
+        .await?;
+
+    Ok(checkpoint)
+}
+
+fn read_cursor(directory: &Path) -> Result<Option<i64>> {
+    let path = directory.join(CURSOR_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let checkpoint = contents
+        .trim()
+        .parse::<i64>()
+        .with_context(|| format!("invalid checkpoint in {}", path.display()))?;
+
+    Ok(Some(checkpoint))
+}
+
+fn write_cursor(directory: &Path, checkpoint: i64) -> Result<()> {
+    let path = directory.join(CURSOR_FILE);
+    fs::write(&path, checkpoint.to_string())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(())
+}
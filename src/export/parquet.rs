@@ -0,0 +1,468 @@
+use crate::models::{
+    AcceptCounterOffer, AuctionHistory, Bid, MakeCounterOffer, OfferAccepted, OfferCancelled,
+    OfferDeclined, OfferHistory, OfferPlaced, Sale,
+};
+use anyhow::{anyhow, Context};
+use arrow::array::{
+    ArrayRef, BooleanArray, FixedSizeBinaryArray, Int32Array, Int64Array, ListBuilder, StringArray,
+    StringBuilder, TimestampMillisecondArray, UInt64Array,
+};
+use arrow::record_batch::RecordBatch;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use sui_indexer_alt_framework::Result;
+
+const ADDRESS_LENGTH: i32 = 32;
+
+/// Rows converted to Arrow columns. Amounts are unsigned integers in the smallest unit of their
+/// coin, addresses and object ids 32 bytes, and times UTC milliseconds.
+pub trait ToRecordBatch: Sized {
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch>;
+}
+
+/// Writes the batch to a new Parquet file, replacing any file at the path
+pub fn write(path: &Path, batch: &RecordBatch) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+// Offer events without an owner share their columns
+macro_rules! buyer_event_batch {
+    ($model:ty) => {
+        impl ToRecordBatch for $model {
+            fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+                let batch = RecordBatch::try_from_iter([
+                    (
+                        "domain_name",
+                        strings(rows.iter().map(|r| r.domain_name.as_str())),
+                    ),
+                    (
+                        "address",
+                        addresses(rows.iter().map(|r| Some(r.address.as_str())))?,
+                    ),
+                    (
+                        "value",
+                        amounts(rows.iter().map(|r| Some(r.value.as_str())))?,
+                    ),
+                    (
+                        "coin_type",
+                        strings(rows.iter().map(|r| r.coin_type.as_str())),
+                    ),
+                    (
+                        "checkpoint",
+                        Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.checkpoint)))
+                            as ArrayRef,
+                    ),
+                    (
+                        "tx_digest",
+                        strings(rows.iter().map(|r| r.tx_digest.as_str())),
+                    ),
+                    (
+                        "created_at",
+                        timestamps(rows.iter().map(|r| Some(r.created_at))),
+                    ),
+                ])?;
+
+                Ok(batch)
+            }
+        }
+    };
+}
+
+// Offer events answered by the owner of the domain
+macro_rules! owner_event_batch {
+    ($model:ty) => {
+        impl ToRecordBatch for $model {
+            fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+                let batch = RecordBatch::try_from_iter([
+                    (
+                        "domain_name",
+                        strings(rows.iter().map(|r| r.domain_name.as_str())),
+                    ),
+                    (
+                        "address",
+                        addresses(rows.iter().map(|r| Some(r.address.as_str())))?,
+                    ),
+                    (
+                        "owner",
+                        addresses(rows.iter().map(|r| Some(r.owner.as_str())))?,
+                    ),
+                    (
+                        "value",
+                        amounts(rows.iter().map(|r| Some(r.value.as_str())))?,
+                    ),
+                    (
+                        "coin_type",
+                        strings(rows.iter().map(|r| r.coin_type.as_str())),
+                    ),
+                    (
+                        "checkpoint",
+                        Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.checkpoint)))
+                            as ArrayRef,
+                    ),
+                    (
+                        "tx_digest",
+                        strings(rows.iter().map(|r| r.tx_digest.as_str())),
+                    ),
+                    (
+                        "created_at",
+                        timestamps(rows.iter().map(|r| Some(r.created_at))),
+                    ),
+                ])?;
+
+                Ok(batch)
+            }
+        }
+    };
+}
+
+buyer_event_batch!(OfferPlaced);
+buyer_event_batch!(OfferCancelled);
+buyer_event_batch!(AcceptCounterOffer);
+owner_event_batch!(OfferAccepted);
+owner_event_batch!(OfferDeclined);
+owner_event_batch!(MakeCounterOffer);
+
+impl ToRecordBatch for Bid {
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let mut violations = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            for violation in &row.violations {
+                violations.values().append_value(violation);
+            }
+            violations.append(true);
+        }
+
+        let batch = RecordBatch::try_from_iter([
+            (
+                "auction_id",
+                addresses(rows.iter().map(|r| Some(r.auction_id.as_str())))?,
+            ),
+            (
+                "domain_name",
+                strings(rows.iter().map(|r| r.domain_name.as_str())),
+            ),
+            (
+                "bidder",
+                addresses(rows.iter().map(|r| Some(r.bidder.as_str())))?,
+            ),
+            (
+                "amount",
+                amounts(rows.iter().map(|r| Some(r.amount.as_str())))?,
+            ),
+            (
+                "coin_type",
+                strings(rows.iter().map(|r| r.coin_type.as_str())),
+            ),
+            (
+                "is_valid",
+                Arc::new(BooleanArray::from_iter(
+                    rows.iter().map(|r| Some(r.is_valid)),
+                )) as ArrayRef,
+            ),
+            ("violations", Arc::new(violations.finish()) as ArrayRef),
+            (
+                "checkpoint",
+                Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.checkpoint))) as ArrayRef,
+            ),
+            (
+                "tx_digest",
+                strings(rows.iter().map(|r| r.tx_digest.as_str())),
+            ),
+            (
+                "created_at",
+                timestamps(rows.iter().map(|r| Some(r.created_at))),
+            ),
+        ])?;
+
+        Ok(batch)
+    }
+}
+
+impl ToRecordBatch for Sale {
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "domain_name",
+                strings(rows.iter().map(|r| r.domain_name.as_str())),
+            ),
+            (
+                "seller",
+                addresses(rows.iter().map(|r| Some(r.seller.as_str())))?,
+            ),
+            (
+                "buyer",
+                addresses(rows.iter().map(|r| Some(r.buyer.as_str())))?,
+            ),
+            (
+                "price",
+                amounts(rows.iter().map(|r| Some(r.price.as_str())))?,
+            ),
+            (
+                "coin_type",
+                strings(rows.iter().map(|r| r.coin_type.as_str())),
+            ),
+            (
+                "sale_type",
+                strings(rows.iter().map(|r| r.sale_type.as_str())),
+            ),
+            (
+                "checkpoint",
+                Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.checkpoint))) as ArrayRef,
+            ),
+            (
+                "tx_digest",
+                strings(rows.iter().map(|r| r.tx_digest.as_str())),
+            ),
+            (
+                "created_at",
+                timestamps(rows.iter().map(|r| Some(r.created_at))),
+            ),
+        ])?;
+
+        Ok(batch)
+    }
+}
+
+impl ToRecordBatch for OfferHistory {
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "offer_id",
+                Arc::new(Int32Array::from_iter_values(
+                    rows.iter().map(|r| r.offer_id),
+                )) as ArrayRef,
+            ),
+            (
+                "domain_name",
+                strings(rows.iter().map(|r| r.domain_name.as_str())),
+            ),
+            (
+                "buyer",
+                addresses(rows.iter().map(|r| Some(r.buyer.as_str())))?,
+            ),
+            ("owner", addresses(rows.iter().map(|r| r.owner.as_deref()))?),
+            ("status", strings(rows.iter().map(|r| r.status.as_str()))),
+            (
+                "stale_reason",
+                optional_strings(rows.iter().map(|r| r.stale_reason.as_deref())),
+            ),
+            (
+                "initial_value",
+                amounts(rows.iter().map(|r| Some(r.initial_value.as_str())))?,
+            ),
+            (
+                "value",
+                amounts(rows.iter().map(|r| Some(r.value.as_str())))?,
+            ),
+            (
+                "buyer_value",
+                amounts(rows.iter().map(|r| Some(r.buyer_value.as_str())))?,
+            ),
+            (
+                "counter_value",
+                amounts(rows.iter().map(|r| r.counter_value.as_deref()))?,
+            ),
+            (
+                "locked_value",
+                amounts(rows.iter().map(|r| Some(r.locked_value.as_str())))?,
+            ),
+            (
+                "coin_type",
+                strings(rows.iter().map(|r| r.coin_type.as_str())),
+            ),
+            (
+                "last_tx_digest",
+                strings(rows.iter().map(|r| r.last_tx_digest.as_str())),
+            ),
+            (
+                "created_at",
+                timestamps(rows.iter().map(|r| Some(r.created_at))),
+            ),
+            (
+                "updated_at",
+                timestamps(rows.iter().map(|r| Some(r.updated_at))),
+            ),
+            (
+                "valid_from_checkpoint",
+                Arc::new(Int64Array::from_iter_values(
+                    rows.iter().map(|r| r.valid_from_checkpoint),
+                )) as ArrayRef,
+            ),
+            (
+                "valid_to_checkpoint",
+                Arc::new(Int64Array::from_iter(
+                    rows.iter().map(|r| r.valid_to_checkpoint),
+                )) as ArrayRef,
+            ),
+        ])?;
+
+        Ok(batch)
+    }
+}
+
+impl ToRecordBatch for AuctionHistory {
+    fn record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "auction_id",
+                addresses(rows.iter().map(|r| Some(r.auction_id.as_str())))?,
+            ),
+            (
+                "domain_name",
+                strings(rows.iter().map(|r| r.domain_name.as_str())),
+            ),
+            (
+                "owner",
+                addresses(rows.iter().map(|r| Some(r.owner.as_str())))?,
+            ),
+            ("status", strings(rows.iter().map(|r| r.status.as_str()))),
+            (
+                "start_time",
+                timestamps(
+                    rows.iter()
+                        .map(|r| DateTime::from_timestamp_millis(r.start_time)),
+                ),
+            ),
+            (
+                "end_time",
+                timestamps(
+                    rows.iter()
+                        .map(|r| DateTime::from_timestamp_millis(r.end_time)),
+                ),
+            ),
+            (
+                "min_bid",
+                amounts(rows.iter().map(|r| Some(r.min_bid.as_str())))?,
+            ),
+            (
+                "winner",
+                addresses(rows.iter().map(|r| r.winner.as_deref()))?,
+            ),
+            ("amount", amounts(rows.iter().map(|r| r.amount.as_deref()))?),
+            (
+                "locked_value",
+                amounts(rows.iter().map(|r| Some(r.locked_value.as_str())))?,
+            ),
+            (
+                "highest_bid",
+                amounts(rows.iter().map(|r| r.highest_bid.as_deref()))?,
+            ),
+            (
+                "invalid_bid_count",
+                Arc::new(Int32Array::from_iter_values(
+                    rows.iter().map(|r| r.invalid_bid_count),
+                )) as ArrayRef,
+            ),
+            (
+                "coin_type",
+                strings(rows.iter().map(|r| r.coin_type.as_str())),
+            ),
+            (
+                "last_tx_digest",
+                strings(rows.iter().map(|r| r.last_tx_digest.as_str())),
+            ),
+            (
+                "created_at",
+                timestamps(rows.iter().map(|r| Some(r.created_at))),
+            ),
+            (
+                "updated_at",
+                timestamps(rows.iter().map(|r| Some(r.updated_at))),
+            ),
+            (
+                "valid_from_checkpoint",
+                Arc::new(Int64Array::from_iter_values(
+                    rows.iter().map(|r| r.valid_from_checkpoint),
+                )) as ArrayRef,
+            ),
+            (
+                "valid_to_checkpoint",
+                Arc::new(Int64Array::from_iter(
+                    rows.iter().map(|r| r.valid_to_checkpoint),
+                )) as ArrayRef,
+            ),
+        ])?;
+
+        Ok(batch)
+    }
+}
+
+fn strings<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+fn optional_strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(StringArray::from_iter(values))
+}
+
+// Amounts are stored as decimal strings of u64 values
+fn amounts<'a>(values: impl Iterator<Item = Option<&'a str>>) -> Result<ArrayRef> {
+    let values = values
+        .map(|value| {
+            value
+                .map(|value| {
+                    value
+                        .parse::<u64>()
+                        .with_context(|| format!("invalid amount {:?}", value))
+                })
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Arc::new(UInt64Array::from(values)))
+}
+
+// Addresses and object ids are stored as `0x` hex strings, possibly without leading zeros
+fn addresses<'a>(values: impl Iterator<Item = Option<&'a str>>) -> Result<ArrayRef> {
+    let values = values
+        .map(|value| value.map(address_bytes).transpose())
+        .collect::<Result<Vec<_>>>()?;
+
+    let array =
+        FixedSizeBinaryArray::try_from_sparse_iter_with_size(values.into_iter(), ADDRESS_LENGTH)?;
+
+    Ok(Arc::new(array))
+}
+
+fn address_bytes(value: &str) -> Result<Vec<u8>> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    let digits = if digits.len() % 2 == 1 {
+        format!("0{}", digits)
+    } else {
+        digits.to_string()
+    };
+
+    let bytes = hex::decode(&digits).with_context(|| format!("invalid address {:?}", value))?;
+    if bytes.len() > ADDRESS_LENGTH as usize {
+        return Err(anyhow!(
+            "address {:?} is longer than {} bytes",
+            value,
+            ADDRESS_LENGTH
+        ));
+    }
+
+    let mut address = vec![0; ADDRESS_LENGTH as usize - bytes.len()];
+    address.extend(bytes);
+    Ok(address)
+}
+
+fn timestamps(values: impl Iterator<Item = Option<DateTime<Utc>>>) -> ArrayRef {
+    let millis = values.map(|value| value.map(|value| value.timestamp_millis()));
+    Arc::new(TimestampMillisecondArray::from_iter(millis).with_timezone("UTC"))
+}
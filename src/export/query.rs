@@ -0,0 +1,438 @@
+use super::{Dataset, ExportArgs, Format};
+use crate::models::{
+    AcceptCounterOffer, Auction, AuctionHistory, AuctionStatus, Bid, MakeCounterOffer, MarketEvent,
+    Offer, OfferAccepted, OfferCancelled, OfferDeclined, OfferHistory, OfferPlaced, OfferStatus,
+    Sale,
+};
+use crate::schema::{
+    accept_counter_offer, auctions, auctions_history, bids, make_counter_offer, market_events,
+    offer_accepted, offer_cancelled, offer_declined, offer_placed, offers, offers_history, sales,
+};
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::info;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Filters of csv and json exports. Datasets without a column for a filter reject it.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ExportFilter {
+    #[clap(long)]
+    pub domain: Option<String>,
+
+    /// Address taking part in the rows, e.g. as buyer, owner, seller or bidder
+    #[clap(long)]
+    pub address: Option<String>,
+
+    /// Status of offers or auctions as stored in the database, e.g. `placed` or `ended-no-bids`
+    #[clap(long)]
+    pub status: Option<String>,
+
+    /// Type of market events, e.g. `bid-placed`
+    #[clap(long)]
+    pub event_type: Option<String>,
+
+    /// Rows from this time on, e.g. `2025-07-01T00:00:00Z`
+    #[clap(long)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Rows before this time
+    #[clap(long)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ExportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.domain.is_none()
+            && self.address.is_none()
+            && self.status.is_none()
+            && self.event_type.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+
+    // Fails if the filter is set, for datasets without a column to apply it to
+    fn reject<T>(filter: &Option<T>, flag: &str, dataset: Dataset) -> Result<()> {
+        if filter.is_some() {
+            bail!("{} doesn't apply to {}", flag, dataset.name());
+        }
+
+        Ok(())
+    }
+}
+
+/// A row of a dataset exported as csv or json
+#[async_trait]
+pub trait QueryRow: Sized + Send {
+    /// Rows of the dataset matching the filter, oldest first
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>>;
+
+    /// The row as a JSON object, its fields in column order
+    fn to_record(&self) -> Result<Value>;
+}
+
+// Event tables only differ by their model and the columns holding addresses
+macro_rules! event_query {
+    ($model:ty, $table:ident, $dataset:expr, $address:ident $(, $other:ident)*) => {
+        #[async_trait]
+        impl QueryRow for $model {
+            async fn query<'a>(
+                conn: &mut Connection<'a>,
+                filter: &ExportFilter,
+            ) -> Result<Vec<Self>> {
+                ExportFilter::reject(&filter.status, "--status", $dataset)?;
+                ExportFilter::reject(&filter.event_type, "--event-type", $dataset)?;
+
+                let mut query = $table::table.select(<$model>::as_select()).into_boxed();
+
+                if let Some(domain) = &filter.domain {
+                    query = query.filter($table::domain_name.eq(domain.clone()));
+                }
+                if let Some(address) = &filter.address {
+                    query = query.filter(
+                        $table::$address
+                            .eq(address.clone())
+                            $(.or($table::$other.eq(address.clone())))*,
+                    );
+                }
+                if let Some(since) = filter.since {
+                    query = query.filter($table::created_at.ge(since));
+                }
+                if let Some(until) = filter.until {
+                    query = query.filter($table::created_at.lt(until));
+                }
+
+                let rows = query.order($table::id.asc()).load(conn).await?;
+                Ok(rows)
+            }
+
+            fn to_record(&self) -> Result<Value> {
+                Ok(serde_json::to_value(self)?)
+            }
+        }
+    };
+}
+
+event_query!(OfferPlaced, offer_placed, Dataset::OfferPlaced, address);
+event_query!(
+    OfferCancelled,
+    offer_cancelled,
+    Dataset::OfferCancelled,
+    address
+);
+event_query!(
+    OfferAccepted,
+    offer_accepted,
+    Dataset::OfferAccepted,
+    address,
+    owner
+);
+event_query!(
+    OfferDeclined,
+    offer_declined,
+    Dataset::OfferDeclined,
+    address,
+    owner
+);
+event_query!(
+    MakeCounterOffer,
+    make_counter_offer,
+    Dataset::MakeCounterOffer,
+    address,
+    owner
+);
+event_query!(
+    AcceptCounterOffer,
+    accept_counter_offer,
+    Dataset::AcceptCounterOffer,
+    address
+);
+event_query!(Bid, bids, Dataset::Bids, bidder);
+event_query!(Sale, sales, Dataset::Sales, seller, buyer);
+
+#[async_trait]
+impl QueryRow for Offer {
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>> {
+        ExportFilter::reject(&filter.event_type, "--event-type", Dataset::Offers)?;
+
+        let mut query = offers::table.select(Offer::as_select()).into_boxed();
+
+        if let Some(domain) = &filter.domain {
+            query = query.filter(offers::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &filter.address {
+            query = query.filter(
+                offers::buyer
+                    .eq(address.clone())
+                    .or(offers::owner.eq(address.clone())),
+            );
+        }
+        if let Some(status) = &filter.status {
+            query = query.filter(offers::status.eq(status.parse::<OfferStatus>()?));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(offers::created_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(offers::created_at.lt(until));
+        }
+
+        let rows = query.order(offers::id.asc()).load(conn).await?;
+        Ok(rows)
+    }
+
+    fn to_record(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+// States of offers are filtered by the time they began
+#[async_trait]
+impl QueryRow for OfferHistory {
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>> {
+        ExportFilter::reject(&filter.event_type, "--event-type", Dataset::OffersHistory)?;
+
+        let mut query = offers_history::table
+            .select(OfferHistory::as_select())
+            .into_boxed();
+
+        if let Some(domain) = &filter.domain {
+            query = query.filter(offers_history::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &filter.address {
+            query = query.filter(
+                offers_history::buyer
+                    .eq(address.clone())
+                    .or(offers_history::owner.eq(address.clone())),
+            );
+        }
+        if let Some(status) = &filter.status {
+            query = query.filter(offers_history::status.eq(status.parse::<OfferStatus>()?));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(offers_history::updated_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(offers_history::updated_at.lt(until));
+        }
+
+        let rows = query
+            .order(offers_history::history_id.asc())
+            .load(conn)
+            .await?;
+        Ok(rows)
+    }
+
+    fn to_record(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[async_trait]
+impl QueryRow for Auction {
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>> {
+        ExportFilter::reject(&filter.event_type, "--event-type", Dataset::Auctions)?;
+
+        let mut query = auctions::table.select(Auction::as_select()).into_boxed();
+
+        if let Some(domain) = &filter.domain {
+            query = query.filter(auctions::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &filter.address {
+            query = query.filter(
+                auctions::owner
+                    .eq(address.clone())
+                    .or(auctions::winner.eq(address.clone())),
+            );
+        }
+        if let Some(status) = &filter.status {
+            query = query.filter(auctions::status.eq(status.parse::<AuctionStatus>()?));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(auctions::created_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(auctions::created_at.lt(until));
+        }
+
+        let rows = query
+            .order((auctions::created_at.asc(), auctions::auction_id.asc()))
+            .load(conn)
+            .await?;
+        Ok(rows)
+    }
+
+    fn to_record(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+// States of auctions are filtered by the time they began
+#[async_trait]
+impl QueryRow for AuctionHistory {
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>> {
+        ExportFilter::reject(&filter.event_type, "--event-type", Dataset::AuctionsHistory)?;
+
+        let mut query = auctions_history::table
+            .select(AuctionHistory::as_select())
+            .into_boxed();
+
+        if let Some(domain) = &filter.domain {
+            query = query.filter(auctions_history::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &filter.address {
+            query = query.filter(
+                auctions_history::owner
+                    .eq(address.clone())
+                    .or(auctions_history::winner.eq(address.clone())),
+            );
+        }
+        if let Some(status) = &filter.status {
+            query = query.filter(auctions_history::status.eq(status.parse::<AuctionStatus>()?));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(auctions_history::updated_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(auctions_history::updated_at.lt(until));
+        }
+
+        let rows = query
+            .order(auctions_history::history_id.asc())
+            .load(conn)
+            .await?;
+        Ok(rows)
+    }
+
+    fn to_record(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+#[async_trait]
+impl QueryRow for MarketEvent {
+    async fn query<'a>(conn: &mut Connection<'a>, filter: &ExportFilter) -> Result<Vec<Self>> {
+        ExportFilter::reject(&filter.status, "--status", Dataset::MarketEvents)?;
+
+        let mut query = market_events::table
+            .select(MarketEvent::as_select())
+            .into_boxed();
+
+        if let Some(domain) = &filter.domain {
+            query = query.filter(market_events::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &filter.address {
+            query = query.filter(market_events::addresses.contains(vec![address.clone()]));
+        }
+        if let Some(event_type) = &filter.event_type {
+            query = query.filter(market_events::event_type.eq(event_type.clone()));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(market_events::created_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(market_events::created_at.lt(until));
+        }
+
+        let rows = query.order(market_events::id.asc()).load(conn).await?;
+        Ok(rows)
+    }
+
+    // Same shape as in the live feed and webhooks
+    fn to_record(&self) -> Result<Value> {
+        Ok(self.to_json())
+    }
+}
+
+/// Writes the rows of the single selected dataset matching the filter as csv or json, to the
+/// output file or stdout
+pub async fn run<'a>(conn: &mut Connection<'a>, args: &ExportArgs) -> Result<()> {
+    let [dataset] = args.datasets[..] else {
+        bail!("csv and json exports take exactly one dataset");
+    };
+
+    match dataset {
+        Dataset::OfferPlaced => export::<OfferPlaced>(conn, args).await,
+        Dataset::OfferCancelled => export::<OfferCancelled>(conn, args).await,
+        Dataset::OfferAccepted => export::<OfferAccepted>(conn, args).await,
+        Dataset::OfferDeclined => export::<OfferDeclined>(conn, args).await,
+        Dataset::MakeCounterOffer => export::<MakeCounterOffer>(conn, args).await,
+        Dataset::AcceptCounterOffer => export::<AcceptCounterOffer>(conn, args).await,
+        Dataset::Bids => export::<Bid>(conn, args).await,
+        Dataset::Sales => export::<Sale>(conn, args).await,
+        Dataset::OffersHistory => export::<OfferHistory>(conn, args).await,
+        Dataset::AuctionsHistory => export::<AuctionHistory>(conn, args).await,
+        Dataset::Offers => export::<Offer>(conn, args).await,
+        Dataset::Auctions => export::<Auction>(conn, args).await,
+        Dataset::MarketEvents => export::<MarketEvent>(conn, args).await,
+    }
+}
+
+async fn export<'a, T: QueryRow>(conn: &mut Connection<'a>, args: &ExportArgs) -> Result<()> {
+    let rows = T::query(conn, &args.filter).await?;
+    let records = rows
+        .iter()
+        .map(QueryRow::to_record)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("failed to create {}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    match args.format {
+        Format::Csv => write_csv(&mut out, &records)?,
+        Format::Json => write_json(&mut out, &records)?,
+        Format::Parquet => unreachable!("Parquet exports are written by partition"),
+    }
+    out.flush()?;
+
+    info!("Exported {} rows", records.len());
+    Ok(())
+}
+
+// Columns are the fields of the first row. Nested values are written as JSON and nulls as empty
+// cells.
+fn write_csv(out: &mut dyn Write, records: &[Value]) -> Result<()> {
+    let Some(Value::Object(first)) = records.first() else {
+        return Ok(());
+    };
+    let columns: Vec<String> = first.keys().cloned().collect();
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(&columns)?;
+
+    for record in records {
+        writer.write_record(columns.iter().map(|column| match record.get(column) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        }))?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+// An array with one row per line, so large exports stay readable with line-based tools
+fn write_json(out: &mut dyn Write, records: &[Value]) -> Result<()> {
+    writeln!(out, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        let separator = if i + 1 < records.len() { "," } else { "" };
+        writeln!(out, "{}{}", record, separator)?;
+    }
+    writeln!(out, "]")?;
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use crate::models::MarketEvent;
+use crate::schema::{market_events, watermarks};
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use axum::Router;
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use futures::Stream;
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_indexer_alt_framework::postgres::Db;
+use sui_indexer_alt_framework::Result;
+
+/// Pipelines publishing to `market_events`. The feed only serves checkpoints all of them have
+/// committed, so a checkpoint is never sent before every event in it is known.
+pub const FEED_PIPELINES: [&str; 2] = ["offers", "auctions"];
+
+/// Settings of the live feed server
+#[derive(clap::Args, Debug, Clone)]
+pub struct FeedArgs {
+    #[clap(long, default_value = "0.0.0.0:8080")]
+    pub listen_address: SocketAddr,
+
+    /// Wait between polls of the events table when a client is caught up
+    #[clap(long, default_value_t = 1)]
+    pub poll_interval_secs: u64,
+
+    /// Checkpoints read per poll, so clients resuming from far behind catch up in steps
+    #[clap(long, default_value_t = 10_000)]
+    pub checkpoint_span: i64,
+}
+
+/// Query of `GET /events`. Event types are separated by commas, and `after` is the checkpoint to
+/// resume after, defaulting to the `Last-Event-ID` sent by reconnecting clients.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FeedFilter {
+    pub domain: Option<String>,
+    pub address: Option<String>,
+    pub event_type: Option<String>,
+    pub after: Option<i64>,
+}
+
+struct FeedState {
+    db: Db,
+    args: FeedArgs,
+}
+
+// A client's position in the feed: the last checkpoint fully sent, and the events of the
+// checkpoints read after it that are waiting to be sent
+struct Subscription {
+    state: Arc<FeedState>,
+    filter: FeedFilter,
+    event_types: Option<Vec<String>>,
+    cursor: Option<i64>,
+    pending: VecDeque<Event>,
+}
+
+/// Serves the committed marketplace events as Server-Sent Events on `GET /events`, until the
+/// process is stopped.
+///
+/// Every event is sent with its type as the SSE event name and `MarketEvent::to_json` as data.
+/// Once all the events of a range of checkpoints are sent, a `checkpoint` event carries the last
+/// checkpoint as its id: clients reconnecting with it as `Last-Event-ID` (or `after`) resume
+/// without gaps, possibly receiving the events after it a second time.
+pub async fn serve(db: Db, args: FeedArgs) -> Result<()> {
+    let listen_address = args.listen_address;
+    let state = Arc::new(FeedState { db, args });
+
+    let app = Router::new()
+        .route("/events", get(events))
+        .with_state(state);
+
+    info!("Serving the live feed on {}", listen_address);
+
+    let listener = tokio::net::TcpListener::bind(listen_address).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+async fn events(
+    State(state): State<Arc<FeedState>>,
+    headers: HeaderMap,
+    Query(filter): Query<FeedFilter>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok());
+
+    let event_types = filter.event_type.as_ref().map(|event_types| {
+        event_types
+            .split(',')
+            .map(|event_type| event_type.trim().to_string())
+            .collect()
+    });
+
+    let subscription = Subscription {
+        state,
+        cursor: filter.after.or(last_event_id),
+        filter,
+        event_types,
+        pending: VecDeque::new(),
+    };
+
+    let stream = futures::stream::unfold(subscription, |mut subscription| async move {
+        match subscription.next_event().await {
+            Ok(event) => Some((Ok(event), subscription)),
+            Err(e) => {
+                // Ending the stream makes the client reconnect from its last checkpoint
+                warn!("Closing live feed stream: {}", e);
+                None
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+impl Subscription {
+    async fn next_event(&mut self) -> Result<Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+
+            if !self.poll().await? {
+                let interval = Duration::from_secs(self.state.args.poll_interval_secs);
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+
+    // Reads the events of the committed checkpoints after the cursor. Returns whether the cursor
+    // moved.
+    async fn poll(&mut self) -> Result<bool> {
+        let mut conn = self.state.db.connect().await?;
+
+        let watermarks: Vec<i64> = watermarks::table
+            .filter(watermarks::pipeline.eq_any(FEED_PIPELINES))
+            .select(watermarks::checkpoint_hi_inclusive)
+            .load(&mut conn)
+            .await?;
+
+        // Nothing is served until every pipeline has committed something
+        if watermarks.len() < FEED_PIPELINES.len() {
+            return Ok(false);
+        }
+        let Some(committed) = watermarks.into_iter().min() else {
+            return Ok(false);
+        };
+
+        // New clients start from the latest committed checkpoint
+        let Some(cursor) = self.cursor else {
+            self.advance(committed);
+            return Ok(true);
+        };
+
+        if committed <= cursor {
+            return Ok(false);
+        }
+
+        let hi = committed.min(cursor.saturating_add(self.state.args.checkpoint_span));
+
+        let mut query = market_events::table
+            .filter(market_events::checkpoint.gt(cursor))
+            .filter(market_events::checkpoint.le(hi))
+            .select(MarketEvent::as_select())
+            .into_boxed();
+
+        if let Some(domain) = &self.filter.domain {
+            query = query.filter(market_events::domain_name.eq(domain.clone()));
+        }
+        if let Some(address) = &self.filter.address {
+            query = query.filter(market_events::addresses.contains(vec![address.clone()]));
+        }
+        if let Some(event_types) = &self.event_types {
+            query = query.filter(market_events::event_type.eq_any(event_types.clone()));
+        }
+
+        let events: Vec<MarketEvent> = query
+            .order((market_events::checkpoint.asc(), market_events::id.asc()))
+            .load(&mut conn)
+            .await?;
+
+        for event in events {
+            self.pending.push_back(
+                Event::default()
+                    .event(&event.event_type)
+                    .data(event.to_json().to_string()),
+            );
+        }
+
+        self.advance(hi);
+        Ok(true)
+    }
+
+    fn advance(&mut self, checkpoint: i64) {
+        self.cursor = Some(checkpoint);
+        self.pending.push_back(
+            Event::default()
+                .event("checkpoint")
+                .id(checkpoint.to_string())
+                .data(serde_json::json!({ "checkpoint": checkpoint }).to_string()),
+        );
+    }
+}
@@ -1,15 +1,24 @@
+use crate::dry_run::DryRunValue;
 use crate::events::{
-    convert_domain_name, try_deserialize_event, AuctionCancelledEvent, AuctionCreatedEvent,
-    AuctionFinalizedEvent, BidPlacedEvent,
+    convert_domain_name, event_coin_type, event_name, try_deserialize_event,
+    AuctionCancelledEvent, AuctionCreatedEvent, AuctionFinalizedEvent, BidPlacedEvent,
+    SUI_COIN_TYPE,
 };
-use crate::models::{Auction, AuctionStatus, Bid, UpdateAuction};
+use crate::models::{
+    AccountActivity, ActivityRole, Auction, AuctionStatus, Bid, EscrowKind, NewMarketEvent, Sale,
+    SaleType, UpdateAuction,
+};
+use crate::projections::escrow::{self, EscrowContext};
+use crate::projections::stats::TouchedPeriods;
+use crate::projections::{accounts, changes, domains, market_events, sales, stale_offers, stats};
 use crate::schema::{auctions, bids};
 use anyhow::{Context, Error};
 use async_trait::async_trait;
 use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
 use diesel::{ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
-use log::{error, info};
+use log::{error, info, warn};
 use std::sync::Arc;
 use sui_indexer_alt_framework::postgres::{Connection, Db};
 use sui_indexer_alt_framework::pipeline::sequential::Handler;
@@ -25,6 +34,30 @@ pub enum AuctionEvent {
     Cancelled(AuctionCancelledEvent),
     Finalized(AuctionFinalizedEvent),
     Bid(BidPlacedEvent),
+    // Emitted last for every checkpoint so derived statuses advance even without auction events
+    Clock,
+}
+
+/// Reasons a bid can be flagged as invalid against the rules of its auction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BidViolation {
+    BelowMinBid,
+    NotAboveHighestBid,
+    BeforeStart,
+    AfterEnd,
+    AuctionNotActive,
+}
+
+impl BidViolation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BidViolation::BelowMinBid => "below-min-bid",
+            BidViolation::NotAboveHighestBid => "not-above-highest-bid",
+            BidViolation::BeforeStart => "before-start",
+            BidViolation::AfterEnd => "after-end",
+            BidViolation::AuctionNotActive => "auction-not-active",
+        }
+    }
 }
 
 #[derive(FieldCount, Clone)]
@@ -32,6 +65,123 @@ pub struct AuctionValue {
     event: AuctionEvent,
     created_at: DateTime<Utc>,
     tx_digest: String,
+    // Index of the event among the events of its transaction
+    event_seq: u64,
+    checkpoint: u64,
+    epoch: u64,
+    coin_type: String,
+}
+
+impl AuctionValue {
+    fn activity(
+        &self,
+        address: String,
+        role: ActivityRole,
+        action: &str,
+        domain_name: &[u8],
+        counterparty: Option<String>,
+        amount: Option<u64>,
+    ) -> AccountActivity {
+        AccountActivity {
+            address,
+            role,
+            action: action.to_string(),
+            domain_name: convert_domain_name(domain_name),
+            counterparty,
+            amount: amount.map(|amount| amount.to_string()),
+            checkpoint: self.checkpoint as i64,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+            coin_type: self.coin_type.clone(),
+        }
+    }
+
+    fn escrow_context(&self, domain_name: &str) -> EscrowContext {
+        EscrowContext {
+            domain_name: domain_name.to_string(),
+            coin_type: self.coin_type.clone(),
+            checkpoint: self.checkpoint,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+        }
+    }
+
+    fn market_event(
+        &self,
+        event_type: &str,
+        domain_name: &[u8],
+        addresses: Vec<String>,
+        data: serde_json::Value,
+    ) -> NewMarketEvent {
+        NewMarketEvent {
+            event_type: event_type.to_string(),
+            domain_name: convert_domain_name(domain_name),
+            addresses,
+            data,
+            coin_type: self.coin_type.clone(),
+            checkpoint: self.checkpoint as i64,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+            event_seq: self.event_seq as i64,
+        }
+    }
+}
+
+impl DryRunValue for AuctionValue {
+    // Bids are validated against the indexed auction when committed, so only their insertion is
+    // described
+    fn to_json(&self) -> Option<serde_json::Value> {
+        let (event_type, event, transition) = match &self.event {
+            AuctionEvent::Created(event) => (
+                "AuctionCreatedEvent",
+                serde_json::json!(event),
+                serde_json::json!({
+                    "table": "auctions",
+                    "auction_id": event.auction_id.to_string(),
+                    "status": AuctionStatus::Created.as_str(),
+                }),
+            ),
+            AuctionEvent::Cancelled(event) => (
+                "AuctionCancelledEvent",
+                serde_json::json!(event),
+                serde_json::json!({
+                    "table": "auctions",
+                    "auction_id": event.auction_id.to_string(),
+                    "status": AuctionStatus::Cancelled.as_str(),
+                }),
+            ),
+            AuctionEvent::Finalized(event) => (
+                "AuctionFinalizedEvent",
+                serde_json::json!(event),
+                serde_json::json!({
+                    "table": "auctions",
+                    "auction_id": event.auction_id.to_string(),
+                    "status": AuctionStatus::Finalized.as_str(),
+                    "sale": true,
+                }),
+            ),
+            AuctionEvent::Bid(event) => (
+                "BidPlacedEvent",
+                serde_json::json!(event),
+                serde_json::json!({
+                    "table": "bids",
+                    "auction_id": event.auction_id.to_string(),
+                    "bidder": event.bidder.to_string(),
+                    "amount": event.amount.to_string(),
+                }),
+            ),
+            AuctionEvent::Clock => return None,
+        };
+
+        Some(serde_json::json!({
+            "tx_digest": self.tx_digest,
+            "timestamp": self.created_at.to_rfc3339(),
+            "coin_type": self.coin_type,
+            "event_type": event_type,
+            "event": event,
+            "transition": transition,
+        }))
+    }
 }
 
 pub struct AuctionsHandlerPipeline {
@@ -49,6 +199,8 @@ impl Processor for AuctionsHandlerPipeline {
             i64::try_from(timestamp_ms).context("Timestamp too large to convert to i64")?;
         let created_at: DateTime<Utc> =
             DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+        let epoch = checkpoint.checkpoint_summary.epoch;
 
         Ok(checkpoint
             .transactions
@@ -59,13 +211,17 @@ impl Processor for AuctionsHandlerPipeline {
                 let tx_digest = tx.transaction.digest().to_string();
 
                 if let Some(events) = &tx.events {
-                    for event in &events.data {
+                    for (event_seq, event) in events.data.iter().enumerate() {
                         match self.process_event(event) {
-                            Ok(Some(event)) => {
+                            Ok(Some(decoded)) => {
                                 values.push(AuctionValue {
-                                    event,
+                                    event: decoded,
                                     tx_digest: tx_digest.clone(),
+                                    event_seq: event_seq as u64,
                                     created_at,
+                                    checkpoint: checkpoint_seq,
+                                    epoch,
+                                    coin_type: event_coin_type(event),
                                 });
                             }
                             Ok(None) => {
@@ -87,6 +243,15 @@ impl Processor for AuctionsHandlerPipeline {
                 Some(values)
             })
             .flatten()
+            .chain(std::iter::once(AuctionValue {
+                event: AuctionEvent::Clock,
+                created_at,
+                tx_digest: String::new(),
+                event_seq: 0,
+                checkpoint: checkpoint_seq,
+                epoch,
+                coin_type: SUI_COIN_TYPE.to_string(),
+            }))
             .collect())
     }
 }
@@ -108,13 +273,30 @@ impl Handler for AuctionsHandlerPipeline {
 
         let len = batch.len();
 
-        info!("Processing {} auction events", len);
+        // Every checkpoint has a clock value, which isn't an event
+        let events = batch
+            .iter()
+            .filter(|value| !matches!(value.event, AuctionEvent::Clock))
+            .count();
+        if events > 0 {
+            info!("Processing {} auction events", events);
+        }
+
+        let mut unknown_auction_bids = 0;
 
         for value in batch {
             match &value.event {
                 AuctionEvent::Created(created_event) => {
                     let domain_name = convert_domain_name(&created_event.domain_name);
 
+                    domains::set_active_auction(
+                        conn,
+                        &domain_name,
+                        &created_event.auction_id.to_string(),
+                        value.created_at,
+                    )
+                    .await?;
+
                     diesel::insert_into(auctions::table)
                         .values(vec![Auction {
                             auction_id: created_event.auction_id.to_string(),
@@ -129,10 +311,23 @@ impl Handler for AuctionsHandlerPipeline {
                             updated_at: value.created_at,
                             created_at: value.created_at,
                             last_tx_digest: value.tx_digest.clone(),
+                            invalid_bid_count: 0,
+                            coin_type: value.coin_type.clone(),
+                            locked_value: "0".to_string(),
+                            highest_bid: None,
                         }])
                         .execute(conn)
                         .await
                         .map_err(Into::<Error>::into)?;
+
+                    let auction_id = created_event.auction_id.to_string();
+                    changes::auction_changed(
+                        conn,
+                        &auction_id,
+                        Some(&value.tx_digest),
+                        value.checkpoint,
+                    )
+                    .await?;
                 }
                 AuctionEvent::Cancelled(auction_cancelled) => {
                     let domain_name = convert_domain_name(&auction_cancelled.domain_name);
@@ -155,6 +350,33 @@ impl Handler for AuctionsHandlerPipeline {
                     })
                     .execute(conn)
                     .await?;
+
+                    domains::clear_active_auction(
+                        conn,
+                        &domain_name,
+                        &auction_cancelled.auction_id.to_string(),
+                        value.created_at,
+                    )
+                    .await?;
+
+                    // The highest bidder is refunded
+                    escrow::release_all(
+                        conn,
+                        EscrowKind::Auction,
+                        &auction_cancelled.auction_id.to_string(),
+                        "auction-cancelled",
+                        &value.escrow_context(&domain_name),
+                    )
+                    .await?;
+
+                    let auction_id = auction_cancelled.auction_id.to_string();
+                    changes::auction_changed(
+                        conn,
+                        &auction_id,
+                        Some(&value.tx_digest),
+                        value.checkpoint,
+                    )
+                    .await?;
                 }
                 AuctionEvent::Finalized(auction_finalized) => {
                     let domain_name = convert_domain_name(&auction_finalized.domain_name);
@@ -177,31 +399,210 @@ impl Handler for AuctionsHandlerPipeline {
                     })
                     .execute(conn)
                     .await?;
+
+                    domains::clear_active_auction(
+                        conn,
+                        &domain_name,
+                        &auction_finalized.auction_id.to_string(),
+                        value.created_at,
+                    )
+                    .await?;
+
+                    // The winning bid is paid out to the owner
+                    escrow::release_all(
+                        conn,
+                        EscrowKind::Auction,
+                        &auction_finalized.auction_id.to_string(),
+                        "auction-finalized",
+                        &value.escrow_context(&domain_name),
+                    )
+                    .await?;
+
+                    let auction_id = auction_finalized.auction_id.to_string();
+                    changes::auction_changed(
+                        conn,
+                        &auction_id,
+                        Some(&value.tx_digest),
+                        value.checkpoint,
+                    )
+                    .await?;
+
+                    let seller: Option<String> = auctions::table
+                        .select(auctions::owner)
+                        .filter(auctions::auction_id.eq(auction_finalized.auction_id.to_string()))
+                        .first(conn)
+                        .await
+                        .optional()?;
+
+                    match seller {
+                        Some(seller) => {
+                            sales::record_sale(
+                                conn,
+                                Sale {
+                                    domain_name: domain_name.clone(),
+                                    seller,
+                                    buyer: auction_finalized.winner.to_string(),
+                                    price: auction_finalized.amount.to_string(),
+                                    sale_type: SaleType::Auction,
+                                    checkpoint: Some(value.checkpoint as i64),
+                                    tx_digest: value.tx_digest.clone(),
+                                    created_at: value.created_at,
+                                    coin_type: value.coin_type.clone(),
+                                },
+                            )
+                            .await?;
+                        }
+                        None => {
+                            warn!(
+                                "Could not find auction {} for sale of domain {}",
+                                auction_finalized.auction_id, domain_name
+                            );
+                        }
+                    }
+
+                    let (now, checkpoint) = (value.created_at, value.checkpoint);
+                    stale_offers::invalidate_stale(conn, &domain_name, now, checkpoint).await?;
                 }
                 AuctionEvent::Bid(bid_event) => {
                     let domain_name = convert_domain_name(&bid_event.domain_name);
+                    let auction_id = bid_event.auction_id.to_string();
 
                     info!(
                         "Bid placed for domain {}, auction id {}, bidder {}",
-                        domain_name, bid_event.auction_id, bid_event.bidder
+                        domain_name, auction_id, bid_event.bidder
                     );
 
+                    let auction = auctions::table
+                        .find(&auction_id)
+                        .select(Auction::as_select())
+                        .first(conn)
+                        .await
+                        .optional()?;
+
+                    // Bids reference their auction, so a bid for an unknown auction can't be
+                    // stored. It means the auction was created before the first indexed
+                    // checkpoint, or that its creation was missed.
+                    let Some(auction) = auction else {
+                        error!(
+                            "Skipping bid on unknown auction {} for domain {} by bidder {}",
+                            auction_id, domain_name, bid_event.bidder
+                        );
+                        unknown_auction_bids += 1;
+                        continue;
+                    };
+
+                    let highest_bid = auction
+                        .highest_bid
+                        .as_deref()
+                        .map(str::parse::<u64>)
+                        .transpose()
+                        .context("invalid highest bid")?;
+                    let violations = validate_bid(
+                        &auction,
+                        highest_bid,
+                        bid_event.amount,
+                        value.created_at.timestamp_millis(),
+                    )?;
+
+                    if !violations.is_empty() {
+                        warn!(
+                            "Invalid bid for domain {}, auction id {}, bidder {}: {:?}",
+                            domain_name, auction_id, bid_event.bidder, violations
+                        );
+
+                        diesel::update(auctions::table.filter(auctions::auction_id.eq(&auction_id)))
+                            .set(auctions::invalid_bid_count.eq(auctions::invalid_bid_count + 1))
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    // A new highest bid refunds the previous one
+                    if violations.is_empty() {
+                        diesel::update(auctions::table.filter(auctions::auction_id.eq(&auction_id)))
+                            .set(auctions::highest_bid.eq(bid_event.amount.to_string()))
+                            .execute(conn)
+                            .await?;
+
+                        let context = value.escrow_context(&domain_name);
+
+                        escrow::release_all(
+                            conn,
+                            EscrowKind::Auction,
+                            &auction_id,
+                            "outbid",
+                            &context,
+                        )
+                        .await?;
+                        escrow::lock(
+                            conn,
+                            EscrowKind::Auction,
+                            &auction_id,
+                            &bid_event.bidder.to_string(),
+                            bid_event.amount,
+                            "bid-placed",
+                            &context,
+                        )
+                        .await?;
+                    }
+
+                    changes::auction_changed(
+                        conn,
+                        &auction_id,
+                        Some(&value.tx_digest),
+                        value.checkpoint,
+                    )
+                    .await?;
+
                     diesel::insert_into(bids::table)
                         .values(vec![Bid {
-                            auction_id: bid_event.auction_id.to_string(),
+                            auction_id,
                             domain_name,
                             bidder: bid_event.bidder.to_string(),
                             amount: bid_event.amount.to_string(),
                             created_at: value.created_at,
                             tx_digest: value.tx_digest.clone(),
+                            is_valid: violations.is_empty(),
+                            violations: violations
+                                .iter()
+                                .map(|violation| violation.as_str().to_string())
+                                .collect(),
+                            coin_type: value.coin_type.clone(),
+                            checkpoint: Some(value.checkpoint as i64),
+                            event_seq: Some(value.event_seq as i64),
                         }])
                         .execute(conn)
                         .await
                         .map_err(Into::<Error>::into)?;
                 }
+                AuctionEvent::Clock => {
+                    // Handled once for the whole batch below
+                }
             }
+
+            Self::record_activity(conn, value).await?;
+            Self::publish_event(conn, value).await?;
         }
 
+        // Statuses derived from time are recorded in history at the last checkpoint of the batch,
+        // after all of its events
+        if let Some(last) = batch.iter().max_by_key(|value| value.checkpoint) {
+            Self::update_derived_statuses(conn, last.created_at, last.checkpoint).await?;
+        }
+
+        if unknown_auction_bids > 0 {
+            error!(
+                "Skipped {} bids on auctions that are not indexed, auctions are out of sync",
+                unknown_auction_bids
+            );
+        }
+
+        let mut periods = TouchedPeriods::default();
+        for value in batch {
+            let has_data = !matches!(value.event, AuctionEvent::Clock);
+            periods.add(value.epoch, value.created_at, has_data);
+        }
+        stats::refresh(conn, &periods).await?;
+
         Ok(len)
     }
 }
@@ -216,26 +617,26 @@ impl AuctionsHandlerPipeline {
     fn process_event(&self, event: &Event) -> Result<Option<AuctionEvent>> {
         let event_type = event.type_.to_string();
         if event_type.starts_with(&self.contract_package_id) {
-            if event_type.ends_with("::AuctionCreatedEvent") {
+            if event_name(event) == "AuctionCreatedEvent" {
                 info!("Found Auction event: {} ", event_type);
 
                 let created_event: AuctionCreatedEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(AuctionEvent::Created(created_event)));
-            } else if event_type.ends_with("::AuctionCancelledEvent") {
+            } else if event_name(event) == "AuctionCancelledEvent" {
                 info!("Found Auction event: {} ", event_type);
 
                 let cancel_event: AuctionCancelledEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(AuctionEvent::Cancelled(cancel_event)));
-            } else if event_type.ends_with("::AuctionFinalizedEvent ") {
+            } else if event_name(event) == "AuctionFinalizedEvent" {
                 info!("Found Auction event: {} ", event_type);
 
                 let finalized_event: AuctionFinalizedEvent =
                     try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(AuctionEvent::Finalized(finalized_event)));
-            } else if event_type.ends_with("::BidPlacedEvent ") {
+            } else if event_name(event) == "BidPlacedEvent" {
                 info!("Found Bid event: {} ", event_type);
 
                 let bid_event: BidPlacedEvent = try_deserialize_event(&event.contents)?;
@@ -246,4 +647,435 @@ impl AuctionsHandlerPipeline {
 
         Ok(None)
     }
+
+    async fn get_auction_owner<'a>(
+        conn: &mut Connection<'a>,
+        auction_id: &String,
+    ) -> Result<Option<String>> {
+        let owner = auctions::table
+            .select(auctions::owner)
+            .filter(auctions::auction_id.eq(auction_id))
+            .first(conn)
+            .await
+            .optional()?;
+
+        Ok(owner)
+    }
+
+    // Record the event for every address involved and refresh what the bidders have committed
+    async fn record_activity<'a>(conn: &mut Connection<'a>, value: &AuctionValue) -> Result<()> {
+        let (activity, auction_id) = match &value.event {
+            AuctionEvent::Created(event) => (
+                vec![value.activity(
+                    event.owner.to_string(),
+                    ActivityRole::Seller,
+                    "auction-created",
+                    &event.domain_name,
+                    None,
+                    Some(event.min_bid),
+                )],
+                event.auction_id.to_string(),
+            ),
+            AuctionEvent::Cancelled(event) => (
+                vec![value.activity(
+                    event.owner.to_string(),
+                    ActivityRole::Seller,
+                    "auction-cancelled",
+                    &event.domain_name,
+                    None,
+                    None,
+                )],
+                event.auction_id.to_string(),
+            ),
+            AuctionEvent::Bid(event) => {
+                let auction_id = event.auction_id.to_string();
+                let owner = Self::get_auction_owner(conn, &auction_id).await?;
+
+                (
+                    vec![value.activity(
+                        event.bidder.to_string(),
+                        ActivityRole::Bidder,
+                        "bid-placed",
+                        &event.domain_name,
+                        owner,
+                        Some(event.amount),
+                    )],
+                    auction_id,
+                )
+            }
+            AuctionEvent::Finalized(event) => {
+                let auction_id = event.auction_id.to_string();
+                let owner = Self::get_auction_owner(conn, &auction_id).await?;
+
+                let mut activity = vec![value.activity(
+                    event.winner.to_string(),
+                    ActivityRole::Bidder,
+                    "auction-won",
+                    &event.domain_name,
+                    owner.clone(),
+                    Some(event.amount),
+                )];
+                if let Some(owner) = owner {
+                    activity.push(value.activity(
+                        owner,
+                        ActivityRole::Seller,
+                        "auction-sold",
+                        &event.domain_name,
+                        Some(event.winner.to_string()),
+                        Some(event.amount),
+                    ));
+                }
+
+                (activity, auction_id)
+            }
+            AuctionEvent::Clock => return Ok(()),
+        };
+
+        accounts::record_activity(conn, &activity).await?;
+
+        // Any bid on the auction may have been outbid, refunded or settled
+        let bidders: Vec<String> = bids::table
+            .select(bids::bidder)
+            .filter(bids::auction_id.eq(&auction_id))
+            .distinct()
+            .load(conn)
+            .await?;
+
+        for bidder in bidders {
+            accounts::refresh_open_commitments(conn, &bidder, &value.coin_type, value.created_at)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // Publish the event with its auction, for the live feed and webhooks
+    async fn publish_event<'a>(conn: &mut Connection<'a>, value: &AuctionValue) -> Result<()> {
+        let event = match &value.event {
+            AuctionEvent::Created(event) => {
+                let owner = event.owner.to_string();
+                let data = serde_json::json!({
+                    "auction_id": event.auction_id.to_string(),
+                    "owner": owner,
+                    "start_time": event.start_time,
+                    "end_time": event.end_time,
+                    "min_bid": event.min_bid.to_string(),
+                });
+                value.market_event("auction-created", &event.domain_name, vec![owner], data)
+            }
+            AuctionEvent::Cancelled(event) => {
+                let owner = event.owner.to_string();
+                let data = serde_json::json!({
+                    "auction_id": event.auction_id.to_string(),
+                    "owner": owner,
+                });
+                value.market_event("auction-cancelled", &event.domain_name, vec![owner], data)
+            }
+            AuctionEvent::Bid(event) => {
+                let auction_id = event.auction_id.to_string();
+                let bidder = event.bidder.to_string();
+                let owner = Self::get_auction_owner(conn, &auction_id).await?;
+
+                // The bid was stored by this commit, under its transaction and event index
+                let is_valid: bool = bids::table
+                    .select(bids::is_valid)
+                    .filter(bids::tx_digest.eq(&value.tx_digest))
+                    .filter(bids::event_seq.eq(value.event_seq as i64))
+                    .first(conn)
+                    .await?;
+
+                let data = serde_json::json!({
+                    "auction_id": auction_id,
+                    "bidder": bidder,
+                    "owner": owner,
+                    "amount": event.amount.to_string(),
+                    "is_valid": is_valid,
+                });
+                let addresses = [Some(bidder), owner].into_iter().flatten().collect();
+                value.market_event("bid-placed", &event.domain_name, addresses, data)
+            }
+            AuctionEvent::Finalized(event) => {
+                let auction_id = event.auction_id.to_string();
+                let winner = event.winner.to_string();
+                let seller = Self::get_auction_owner(conn, &auction_id).await?;
+
+                let data = serde_json::json!({
+                    "auction_id": auction_id,
+                    "winner": winner,
+                    "seller": seller,
+                    "amount": event.amount.to_string(),
+                });
+                let addresses = [Some(winner), seller].into_iter().flatten().collect();
+                value.market_event("auction-finalized", &event.domain_name, addresses, data)
+            }
+            AuctionEvent::Clock => return Ok(()),
+        };
+
+        market_events::publish(conn, &event).await?;
+
+        Ok(())
+    }
+
+    // Move auctions between the statuses derived from checkpoint time: auctions that started become
+    // active, and auctions past their end time that were never finalized are marked as ended
+    async fn update_derived_statuses<'a>(
+        conn: &mut Connection<'a>,
+        now: DateTime<Utc>,
+        checkpoint: u64,
+    ) -> Result<()> {
+        let now_ms = now.timestamp_millis();
+
+        let activated: Vec<String> = diesel::update(
+            auctions::table
+                .filter(auctions::status.eq(AuctionStatus::Created))
+                .filter(auctions::start_time.le(now_ms))
+                .filter(auctions::end_time.ge(now_ms)),
+        )
+        .set((
+            auctions::status.eq(AuctionStatus::Active),
+            auctions::updated_at.eq(now),
+        ))
+        .returning(auctions::auction_id)
+        .get_results(conn)
+        .await?;
+
+        let has_valid_bids = diesel::dsl::exists(
+            bids::table
+                .filter(bids::auction_id.eq(auctions::auction_id))
+                .filter(bids::is_valid.eq(true)),
+        );
+
+        let ended: Vec<String> = diesel::update(
+            auctions::table
+                .filter(auctions::status.eq_any([AuctionStatus::Created, AuctionStatus::Active]))
+                .filter(auctions::end_time.lt(now_ms))
+                .filter(has_valid_bids),
+        )
+        .set((
+            auctions::status.eq(AuctionStatus::Ended),
+            auctions::updated_at.eq(now),
+        ))
+        .returning(auctions::auction_id)
+        .get_results(conn)
+        .await?;
+
+        let ended_no_bids: Vec<(String, String)> = diesel::update(
+            auctions::table
+                .filter(auctions::status.eq_any([AuctionStatus::Created, AuctionStatus::Active]))
+                .filter(auctions::end_time.lt(now_ms)),
+        )
+        .set((
+            auctions::status.eq(AuctionStatus::EndedNoBids),
+            auctions::updated_at.eq(now),
+        ))
+        .returning((auctions::auction_id, auctions::domain_name))
+        .get_results(conn)
+        .await?;
+
+        // Auctions that ended without bids will never be finalized, so the domain is free again
+        for (auction_id, domain_name) in &ended_no_bids {
+            domains::clear_active_auction(conn, domain_name, auction_id, now).await?;
+        }
+
+        let derived = activated
+            .iter()
+            .chain(&ended)
+            .chain(ended_no_bids.iter().map(|(auction_id, _)| auction_id));
+        for auction_id in derived {
+            changes::auction_changed(conn, auction_id, None, checkpoint).await?;
+        }
+
+        if !activated.is_empty() || !ended.is_empty() || !ended_no_bids.is_empty() {
+            info!(
+                "Derived auction statuses: {} active, {} ended, {} ended without bids",
+                activated.len(),
+                ended.len(),
+                ended_no_bids.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks a bid against the rules of its auction, returning every rule it breaks
+pub fn validate_bid(
+    auction: &Auction,
+    highest_bid: Option<u64>,
+    amount: u64,
+    timestamp_ms: i64,
+) -> Result<Vec<BidViolation>> {
+    let min_bid = auction
+        .min_bid
+        .parse::<u64>()
+        .context("invalid auction min bid")?;
+
+    let mut violations = Vec::new();
+
+    if amount < min_bid {
+        violations.push(BidViolation::BelowMinBid);
+    }
+    if highest_bid.is_some_and(|highest_bid| amount <= highest_bid) {
+        violations.push(BidViolation::NotAboveHighestBid);
+    }
+    if timestamp_ms < auction.start_time {
+        violations.push(BidViolation::BeforeStart);
+    }
+    if timestamp_ms > auction.end_time {
+        violations.push(BidViolation::AfterEnd);
+    }
+    if !matches!(auction.status, AuctionStatus::Created | AuctionStatus::Active) {
+        violations.push(BidViolation::AuctionNotActive);
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+
+    const START: i64 = 1_000;
+    const END: i64 = 2_000;
+
+    fn auction() -> Auction {
+        let created_at = DateTime::<Utc>::from_timestamp_millis(0).unwrap();
+
+        Auction {
+            auction_id: "0x1".to_string(),
+            domain_name: "example.sui".to_string(),
+            owner: "0x2".to_string(),
+            start_time: START,
+            end_time: END,
+            min_bid: "100".to_string(),
+            winner: None,
+            amount: None,
+            status: AuctionStatus::Active,
+            updated_at: created_at,
+            created_at,
+            last_tx_digest: String::new(),
+            invalid_bid_count: 0,
+            coin_type: SUI_COIN_TYPE.to_string(),
+            locked_value: "0".to_string(),
+            highest_bid: None,
+        }
+    }
+
+    fn value(event: AuctionEvent) -> AuctionValue {
+        AuctionValue {
+            event,
+            created_at: DateTime::<Utc>::from_timestamp_millis(START).unwrap(),
+            tx_digest: "tx".to_string(),
+            event_seq: 0,
+            checkpoint: 7,
+            epoch: 1,
+            coin_type: SUI_COIN_TYPE.to_string(),
+        }
+    }
+
+    #[test]
+    fn bid_dry_run_json() {
+        let auction_id = ObjectID::from_single_byte(1);
+        let bidder = SuiAddress::from(ObjectID::from_single_byte(2));
+        let json = value(AuctionEvent::Bid(BidPlacedEvent {
+            auction_id,
+            domain_name: b"example.sui".to_vec(),
+            bidder,
+            amount: 150,
+        }))
+        .to_json()
+        .unwrap();
+
+        assert_eq!(json["tx_digest"], "tx");
+        assert_eq!(json["timestamp"], "1970-01-01T00:00:01+00:00");
+        assert_eq!(json["coin_type"], SUI_COIN_TYPE);
+        assert_eq!(json["event_type"], "BidPlacedEvent");
+        assert_eq!(json["event"]["domain_name"], "example.sui");
+        assert_eq!(json["event"]["amount"], 150);
+        assert_eq!(
+            json["transition"],
+            serde_json::json!({
+                "table": "bids",
+                "auction_id": auction_id.to_string(),
+                "bidder": bidder.to_string(),
+                "amount": "150",
+            })
+        );
+    }
+
+    #[test]
+    fn clock_has_no_dry_run_json() {
+        assert!(value(AuctionEvent::Clock).to_json().is_none());
+    }
+
+    #[test]
+    fn valid_bid() {
+        assert_eq!(validate_bid(&auction(), None, 100, START).unwrap(), vec![]);
+        assert_eq!(
+            validate_bid(&auction(), Some(150), 151, END).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn below_min_bid() {
+        assert_eq!(
+            validate_bid(&auction(), None, 99, START).unwrap(),
+            vec![BidViolation::BelowMinBid]
+        );
+    }
+
+    #[test]
+    fn not_above_highest_bid() {
+        assert_eq!(
+            validate_bid(&auction(), Some(150), 150, START).unwrap(),
+            vec![BidViolation::NotAboveHighestBid]
+        );
+        assert_eq!(
+            validate_bid(&auction(), Some(150), 120, START).unwrap(),
+            vec![BidViolation::NotAboveHighestBid]
+        );
+    }
+
+    #[test]
+    fn before_start() {
+        assert_eq!(
+            validate_bid(&auction(), None, 100, START - 1).unwrap(),
+            vec![BidViolation::BeforeStart]
+        );
+    }
+
+    #[test]
+    fn after_end() {
+        assert_eq!(
+            validate_bid(&auction(), None, 100, END + 1).unwrap(),
+            vec![BidViolation::AfterEnd]
+        );
+    }
+
+    #[test]
+    fn auction_not_active() {
+        let auction = Auction {
+            status: AuctionStatus::Finalized,
+            ..auction()
+        };
+
+        assert_eq!(
+            validate_bid(&auction, None, 100, START).unwrap(),
+            vec![BidViolation::AuctionNotActive]
+        );
+    }
+
+    #[test]
+    fn every_violation_is_reported() {
+        assert_eq!(
+            validate_bid(&auction(), Some(150), 50, END + 1).unwrap(),
+            vec![
+                BidViolation::BelowMinBid,
+                BidViolation::NotAboveHighestBid,
+                BidViolation::AfterEnd
+            ]
+        );
+    }
 }
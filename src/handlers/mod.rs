@@ -1,3 +1,6 @@
 pub mod offer_events_handler;
 pub mod offers_handler;
 pub mod auctions_handler;
+pub mod name_records_handler;
+pub mod registration_owners_handler;
+pub mod transactions_handler;
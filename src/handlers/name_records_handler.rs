@@ -0,0 +1,278 @@
+use crate::dry_run::DryRunValue;
+use crate::models::NameRecord;
+use crate::projections::stale_offers;
+use crate::registry::{self, Domain, Field};
+use crate::schema::name_records;
+use anyhow::Context;
+use async_trait::async_trait;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::{error, info};
+use std::collections::HashSet;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::sequential::Handler;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_indexer_alt_framework::postgres::{Connection, Db};
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use sui_indexer_alt_framework::FieldCount;
+use sui_indexer_alt_framework::Result;
+use sui_types::effects::TransactionEffectsAPI;
+
+#[derive(Clone)]
+pub enum NameRecordChange {
+    // A name record was added or changed, which covers registrations, renewals and target updates
+    Updated(Field<Domain, registry::NameRecord>),
+    // A name record was removed from the registry, e.g. after its expiration
+    Removed(Field<Domain, registry::NameRecord>),
+    // Emitted once per checkpoint so offers on expiring names go stale without registry changes
+    Clock,
+}
+
+#[derive(FieldCount, Clone)]
+pub struct NameRecordValue {
+    change: NameRecordChange,
+    created_at: DateTime<Utc>,
+    tx_digest: String,
+    checkpoint: u64,
+}
+
+impl DryRunValue for NameRecordValue {
+    // Offers on expired names are invalidated against the indexed records, so clock values are
+    // left out
+    fn to_json(&self) -> Option<serde_json::Value> {
+        let transition = match &self.change {
+            NameRecordChange::Updated(field) => serde_json::json!({
+                "table": "name_records",
+                "domain_name": field.name.to_name(),
+                "nft_id": field.value.nft_id.to_string(),
+                "expiration_timestamp_ms": field.value.expiration_timestamp_ms,
+                "target_address": field.value.target_address.map(|address| address.to_string()),
+            }),
+            NameRecordChange::Removed(field) => serde_json::json!({
+                "table": "name_records",
+                "domain_name": field.name.to_name(),
+                "removed": true,
+            }),
+            NameRecordChange::Clock => return None,
+        };
+
+        Some(serde_json::json!({
+            "tx_digest": self.tx_digest,
+            "timestamp": self.created_at.to_rfc3339(),
+            "transition": transition,
+        }))
+    }
+}
+
+pub struct NameRecordsHandlerPipeline {
+    suins_package_id: String,
+}
+
+impl Processor for NameRecordsHandlerPipeline {
+    const NAME: &'static str = "name_records";
+
+    type Value = NameRecordValue;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let timestamp_ms: u64 = checkpoint.checkpoint_summary.timestamp_ms.into();
+        let timestamp_i64 =
+            i64::try_from(timestamp_ms).context("Timestamp too large to convert to i64")?;
+        let created_at: DateTime<Utc> =
+            DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+
+        let mut values = Vec::new();
+
+        for tx in &checkpoint.transactions {
+            let tx_digest = tx.transaction.digest().to_string();
+
+            let mut changes = Vec::new();
+
+            for object in &tx.output_objects {
+                if let Some(field) = self.process_object(object) {
+                    changes.push(NameRecordChange::Updated(field));
+                }
+            }
+
+            // Removed records are only found in the inputs, as their last version before deletion
+            let deleted: HashSet<_> = tx
+                .effects
+                .deleted()
+                .into_iter()
+                .map(|(object_id, _, _)| object_id)
+                .collect();
+
+            for object in &tx.input_objects {
+                if !deleted.contains(&object.id()) {
+                    continue;
+                }
+
+                if let Some(field) = self.process_object(object) {
+                    changes.push(NameRecordChange::Removed(field));
+                }
+            }
+
+            values.extend(changes.into_iter().map(|change| NameRecordValue {
+                change,
+                created_at,
+                tx_digest: tx_digest.clone(),
+                checkpoint: checkpoint_seq,
+            }));
+        }
+
+        values.push(NameRecordValue {
+            change: NameRecordChange::Clock,
+            created_at,
+            tx_digest: String::new(),
+            checkpoint: checkpoint_seq,
+        });
+
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl Handler for NameRecordsHandlerPipeline {
+    type Store = Db;
+    type Batch = Vec<Self::Value>;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        batch.extend(values);
+    }
+
+    async fn commit<'a>(batch: &Self::Batch, conn: &mut Connection<'a>) -> Result<usize> {
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let len = batch.len();
+
+        info!("Processing {} name record changes", len);
+
+        for value in batch {
+            match &value.change {
+                NameRecordChange::Updated(field) => {
+                    Self::update_name_record(conn, value, field).await?;
+                }
+                NameRecordChange::Removed(field) => {
+                    let domain_name = field.name.to_name();
+
+                    info!("Name record of domain {} removed", domain_name);
+
+                    diesel::update(name_records::table.find(&domain_name))
+                        .set((
+                            name_records::removed_at.eq(Some(value.created_at)),
+                            name_records::checkpoint.eq(value.checkpoint as i64),
+                            name_records::last_tx_digest.eq(&value.tx_digest),
+                            name_records::updated_at.eq(value.created_at),
+                        ))
+                        .execute(conn)
+                        .await?;
+                }
+                NameRecordChange::Clock => {
+                    // Handled once for the whole batch below
+                }
+            }
+        }
+
+        if let Some(last) = batch.iter().max_by_key(|value| value.checkpoint) {
+            stale_offers::invalidate_expired(conn, last.created_at, last.checkpoint).await?;
+        }
+
+        Ok(len)
+    }
+}
+
+impl NameRecordsHandlerPipeline {
+    pub fn new(suins_package_id: String) -> Self {
+        Self { suins_package_id }
+    }
+
+    fn process_object(
+        &self,
+        object: &sui_types::object::Object,
+    ) -> Option<Field<Domain, registry::NameRecord>> {
+        match registry::try_name_record(object, &self.suins_package_id) {
+            Ok(field) => field,
+            Err(e) => {
+                // Should not be reached
+                error!("Error processing name record {}: {}", object.id(), e);
+                panic!("Error processing name record {}: {}", object.id(), e);
+            }
+        }
+    }
+
+    async fn update_name_record<'a>(
+        conn: &mut Connection<'a>,
+        value: &NameRecordValue,
+        field: &Field<Domain, registry::NameRecord>,
+    ) -> Result<()> {
+        let domain_name = field.name.to_name();
+        let record = &field.value;
+
+        let expiration_timestamp_ms = i64::try_from(record.expiration_timestamp_ms)
+            .context("Expiration too large to convert to i64")?;
+        let expires_at = DateTime::<Utc>::from_timestamp_millis(expiration_timestamp_ms)
+            .context("invalid expiration timestamp")?;
+
+        let existing = name_records::table
+            .find(&domain_name)
+            .select(NameRecord::as_select())
+            .first(conn)
+            .await
+            .optional()?;
+
+        let (registered_at, last_renewed_at) = match existing {
+            // A removed record showing up again is a new registration
+            Some(existing) if existing.removed_at.is_none() => {
+                if expiration_timestamp_ms > existing.expiration_timestamp_ms {
+                    info!("Domain {} renewed until {}", domain_name, expires_at);
+                    (existing.registered_at, Some(value.created_at))
+                } else {
+                    (existing.registered_at, existing.last_renewed_at)
+                }
+            }
+            _ => {
+                info!("Domain {} registered until {}", domain_name, expires_at);
+                (value.created_at, None)
+            }
+        };
+
+        let name_record = NameRecord {
+            domain_name,
+            nft_id: record.nft_id.to_string(),
+            target_address: record.target_address.map(|address| address.to_string()),
+            expiration_timestamp_ms,
+            expires_at,
+            registered_at,
+            last_renewed_at,
+            removed_at: None,
+            checkpoint: value.checkpoint as i64,
+            last_tx_digest: value.tx_digest.clone(),
+            updated_at: value.created_at,
+        };
+
+        diesel::insert_into(name_records::table)
+            .values(&name_record)
+            .on_conflict(name_records::domain_name)
+            .do_update()
+            .set((
+                name_records::nft_id.eq(&name_record.nft_id),
+                name_records::target_address.eq(&name_record.target_address),
+                name_records::expiration_timestamp_ms.eq(name_record.expiration_timestamp_ms),
+                name_records::expires_at.eq(name_record.expires_at),
+                name_records::registered_at.eq(name_record.registered_at),
+                name_records::last_renewed_at.eq(name_record.last_renewed_at),
+                name_records::removed_at.eq(name_record.removed_at),
+                name_records::checkpoint.eq(name_record.checkpoint),
+                name_records::last_tx_digest.eq(&name_record.last_tx_digest),
+                name_records::updated_at.eq(name_record.updated_at),
+            ))
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+}
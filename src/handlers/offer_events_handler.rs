@@ -1,6 +1,8 @@
+use crate::dry_run::DryRunValue;
 use crate::events::{
-    convert_domain_name, try_deserialize_event, AcceptCounterOfferEvent, MakeCounterOfferEvent,
-    OfferAcceptedEvent, OfferCancelledEvent, OfferDeclinedEvent, OfferPlacedEvent,
+    convert_domain_name, event_coin_type, event_name, try_deserialize_event,
+    AcceptCounterOfferEvent, MakeCounterOfferEvent, OfferAcceptedEvent, OfferCancelledEvent,
+    OfferDeclinedEvent, OfferPlacedEvent,
 };
 use crate::models::{
     AcceptCounterOffer, MakeCounterOffer, OfferAccepted, OfferCancelled, OfferDeclined, OfferPlaced,
@@ -43,6 +45,20 @@ pub struct OfferHandlerValue {
     pub checkpoint: u64,
 }
 
+impl DryRunValue for OfferHandlerValue {
+    // Rows inserted into the raw event tables
+    fn to_json(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "offer_placed": self.placed,
+            "offer_cancelled": self.cancelled,
+            "offer_accepted": self.accepted,
+            "offer_declined": self.declined,
+            "make_counter_offer": self.make_counter_offer,
+            "accept_counter_offer": self.accept_counter_offer,
+        }))
+    }
+}
+
 pub struct OfferEventsHandlerPipeline {
     contract_package_id: String,
 }
@@ -59,6 +75,8 @@ impl Processor for OfferEventsHandlerPipeline {
         let created_at: DateTime<Utc> =
             DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
 
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+
         let mut placed = Vec::new();
         let mut cancelled = Vec::new();
         let mut accepted = Vec::new();
@@ -70,7 +88,7 @@ impl Processor for OfferEventsHandlerPipeline {
             let tx_digest = tx.transaction.digest().to_string();
             if let Some(events) = &tx.events {
                 for event in &events.data {
-                    match self.process_event(event, &tx_digest, created_at) {
+                    match self.process_event(event, &tx_digest, created_at, checkpoint_seq) {
                         Ok(Some(OfferEventModel::Placed(offer))) => {
                             info!("Processing placed offer for domain: {}", offer.domain_name);
                             placed.push(offer);
@@ -129,7 +147,7 @@ impl Processor for OfferEventsHandlerPipeline {
             declined,
             make_counter_offer,
             accept_counter_offer,
-            checkpoint: checkpoint.checkpoint_summary.sequence_number,
+            checkpoint: checkpoint_seq,
         }];
 
         Ok(result)
@@ -319,10 +337,13 @@ impl OfferEventsHandlerPipeline {
         event: &Event,
         tx_digest: &str,
         created_at: DateTime<Utc>,
+        checkpoint: u64,
     ) -> Result<Option<OfferEventModel>> {
         let event_type = event.type_.to_string();
         if event_type.starts_with(&self.contract_package_id) {
-            if event_type.ends_with("::OfferPlacedEvent") {
+            let coin_type = event_coin_type(event);
+
+            if event_name(event) == "OfferPlacedEvent" {
                 let offer_event: OfferPlacedEvent = try_deserialize_event(&event.contents)?;
 
                 let offer = OfferPlaced {
@@ -331,10 +352,12 @@ impl OfferEventsHandlerPipeline {
                     value: offer_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::Placed(offer)));
-            } else if event_type.ends_with("::OfferCancelledEvent") {
+            } else if event_name(event) == "OfferCancelledEvent" {
                 let cancel_event: OfferCancelledEvent = try_deserialize_event(&event.contents)?;
 
                 let cancellation = OfferCancelled {
@@ -343,10 +366,12 @@ impl OfferEventsHandlerPipeline {
                     value: cancel_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::Cancelled(cancellation)));
-            } else if event_type.ends_with("::OfferAcceptedEvent") {
+            } else if event_name(event) == "OfferAcceptedEvent" {
                 let accepted_event: OfferAcceptedEvent = try_deserialize_event(&event.contents)?;
 
                 let accepted = OfferAccepted {
@@ -356,10 +381,12 @@ impl OfferEventsHandlerPipeline {
                     value: accepted_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::Accepted(accepted)));
-            } else if event_type.ends_with("::OfferDeclinedEvent") {
+            } else if event_name(event) == "OfferDeclinedEvent" {
                 let declined_event: OfferDeclinedEvent = try_deserialize_event(&event.contents)?;
 
                 let decline = OfferDeclined {
@@ -369,10 +396,12 @@ impl OfferEventsHandlerPipeline {
                     value: declined_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::Declined(decline)));
-            } else if event_type.ends_with("::MakeCounterOfferEvent") {
+            } else if event_name(event) == "MakeCounterOfferEvent" {
                 let make_counter_offer_event: MakeCounterOfferEvent =
                     try_deserialize_event(&event.contents)?;
 
@@ -383,10 +412,12 @@ impl OfferEventsHandlerPipeline {
                     value: make_counter_offer_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::MakeCounterOffer(make_counter_offer)));
-            } else if event_type.ends_with("::AcceptCounterOfferEvent") {
+            } else if event_name(event) == "AcceptCounterOfferEvent" {
                 let accept_counter_offer_event: AcceptCounterOfferEvent =
                     try_deserialize_event(&event.contents)?;
 
@@ -396,6 +427,8 @@ impl OfferEventsHandlerPipeline {
                     value: accept_counter_offer_event.value.to_string(),
                     created_at,
                     tx_digest: tx_digest.to_string(),
+                    coin_type,
+                    checkpoint: Some(checkpoint as i64),
                 };
 
                 return Ok(Some(OfferEventModel::AcceptCounterOffer(accept_counter_offer)));
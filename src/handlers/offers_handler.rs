@@ -1,9 +1,17 @@
+use crate::dry_run::DryRunValue;
 use crate::events::{
-    convert_domain_name, try_deserialize_event, AcceptCounterOfferEvent, MakeCounterOfferEvent,
-    OfferAcceptedEvent, OfferCancelledEvent, OfferDeclinedEvent, OfferPlacedEvent,
+    convert_domain_name, event_coin_type, event_name, try_deserialize_event,
+    AcceptCounterOfferEvent, MakeCounterOfferEvent, OfferAcceptedEvent, OfferCancelledEvent,
+    OfferDeclinedEvent, OfferPlacedEvent,
 };
-use crate::models::{Offer, OfferStatus, UpdateOffer};
-use crate::schema::offers;
+use crate::models::{
+    AccountActivity, ActivityRole, EscrowKind, NegotiationRound, NewMarketEvent, Offer,
+    OfferStatus, Sale, SaleType, UpdateOffer,
+};
+use crate::projections::escrow::{self, EscrowContext};
+use crate::projections::stats::TouchedPeriods;
+use crate::projections::{accounts, changes, domains, market_events, sales, stale_offers, stats};
+use crate::schema::{negotiation_rounds, offers};
 use anyhow::{Context, Error};
 use async_trait::async_trait;
 use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
@@ -11,6 +19,7 @@ use diesel::prelude::*;
 use diesel::{ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 use log::{error, info, warn};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use sui_indexer_alt_framework::postgres::{Connection, Db};
 use sui_indexer_alt_framework::pipeline::sequential::Handler;
@@ -31,11 +40,146 @@ pub enum OfferEvent {
     AcceptCounterOffer(AcceptCounterOfferEvent),
 }
 
+impl OfferEvent {
+    pub fn domain_name(&self) -> String {
+        convert_domain_name(match self {
+            OfferEvent::Placed(event) => &event.domain_name,
+            OfferEvent::Cancelled(event) => &event.domain_name,
+            OfferEvent::Accepted(event) => &event.domain_name,
+            OfferEvent::Declined(event) => &event.domain_name,
+            OfferEvent::MakeCounterOffer(event) => &event.domain_name,
+            OfferEvent::AcceptCounterOffer(event) => &event.domain_name,
+        })
+    }
+
+    pub fn buyer(&self) -> SuiAddress {
+        match self {
+            OfferEvent::Placed(event) => event.address,
+            OfferEvent::Cancelled(event) => event.address,
+            OfferEvent::Accepted(event) => event.buyer,
+            OfferEvent::Declined(event) => event.buyer,
+            OfferEvent::MakeCounterOffer(event) => event.buyer,
+            OfferEvent::AcceptCounterOffer(event) => event.buyer,
+        }
+    }
+}
+
 #[derive(FieldCount, Clone)]
 pub struct OfferValue {
     event: OfferEvent,
     created_at: DateTime<Utc>,
     tx_digest: String,
+    // Index of the event among the events of its transaction
+    event_seq: u64,
+    checkpoint: u64,
+    epoch: u64,
+    coin_type: String,
+}
+
+impl OfferValue {
+    fn activity(
+        &self,
+        address: String,
+        role: ActivityRole,
+        action: &str,
+        counterparty: Option<String>,
+        amount: u64,
+    ) -> AccountActivity {
+        AccountActivity {
+            address,
+            role,
+            action: action.to_string(),
+            domain_name: self.event.domain_name(),
+            counterparty,
+            amount: Some(amount.to_string()),
+            checkpoint: self.checkpoint as i64,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+            coin_type: self.coin_type.clone(),
+        }
+    }
+
+    fn escrow_context(&self) -> EscrowContext {
+        EscrowContext {
+            domain_name: self.event.domain_name(),
+            coin_type: self.coin_type.clone(),
+            checkpoint: self.checkpoint,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+        }
+    }
+
+    fn market_event(
+        &self,
+        event_type: &str,
+        addresses: Vec<String>,
+        data: serde_json::Value,
+    ) -> NewMarketEvent {
+        NewMarketEvent {
+            event_type: event_type.to_string(),
+            domain_name: self.event.domain_name(),
+            addresses,
+            data,
+            coin_type: self.coin_type.clone(),
+            checkpoint: self.checkpoint as i64,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+            event_seq: self.event_seq as i64,
+        }
+    }
+}
+
+impl DryRunValue for OfferValue {
+    // The latest offer of the buyer on the domain in the coin moves to the status of the event
+    fn to_json(&self) -> Option<serde_json::Value> {
+        let (event_type, event, status) = match &self.event {
+            OfferEvent::Placed(event) => (
+                "OfferPlacedEvent",
+                serde_json::json!(event),
+                OfferStatus::Placed,
+            ),
+            OfferEvent::Cancelled(event) => (
+                "OfferCancelledEvent",
+                serde_json::json!(event),
+                OfferStatus::Cancelled,
+            ),
+            OfferEvent::Accepted(event) => (
+                "OfferAcceptedEvent",
+                serde_json::json!(event),
+                OfferStatus::Accepted,
+            ),
+            OfferEvent::Declined(event) => (
+                "OfferDeclinedEvent",
+                serde_json::json!(event),
+                OfferStatus::Declined,
+            ),
+            OfferEvent::MakeCounterOffer(event) => (
+                "MakeCounterOfferEvent",
+                serde_json::json!(event),
+                OfferStatus::Countered,
+            ),
+            OfferEvent::AcceptCounterOffer(event) => (
+                "AcceptCounterOfferEvent",
+                serde_json::json!(event),
+                OfferStatus::AcceptedCountered,
+            ),
+        };
+
+        Some(serde_json::json!({
+            "tx_digest": self.tx_digest,
+            "timestamp": self.created_at.to_rfc3339(),
+            "coin_type": self.coin_type,
+            "event_type": event_type,
+            "event": event,
+            "transition": {
+                "table": "offers",
+                "domain_name": self.event.domain_name(),
+                "buyer": self.event.buyer().to_string(),
+                "status": status.as_str(),
+                "sale": matches!(status, OfferStatus::Accepted | OfferStatus::AcceptedCountered),
+            },
+        }))
+    }
 }
 
 pub struct OffersHandlerPipeline {
@@ -53,6 +197,8 @@ impl Processor for OffersHandlerPipeline {
             i64::try_from(timestamp_ms).context("Timestamp too large to convert to i64")?;
         let created_at: DateTime<Utc> =
             DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+        let epoch = checkpoint.checkpoint_summary.epoch;
 
         Ok(checkpoint
             .transactions
@@ -63,13 +209,17 @@ impl Processor for OffersHandlerPipeline {
                 let tx_digest = tx.transaction.digest().to_string();
 
                 if let Some(events) = &tx.events {
-                    for event in &events.data {
+                    for (event_seq, event) in events.data.iter().enumerate() {
                         match self.process_event(event) {
-                            Ok(Some(event)) => {
+                            Ok(Some(decoded)) => {
                                 values.push(OfferValue {
-                                    event,
+                                    event: decoded,
                                     tx_digest: tx_digest.clone(),
+                                    event_seq: event_seq as u64,
                                     created_at,
+                                    checkpoint: checkpoint_seq,
+                                    epoch,
+                                    coin_type: event_coin_type(event),
                                 });
                             }
                             Ok(None) => {
@@ -119,8 +269,8 @@ impl Handler for OffersHandlerPipeline {
                 OfferEvent::Placed(placed_event) => {
                     let domain_name = convert_domain_name(&placed_event.domain_name);
 
-                    diesel::insert_into(offers::table)
-                        .values(vec![Offer {
+                    let id: i32 = diesel::insert_into(offers::table)
+                        .values(Offer {
                             domain_name,
                             buyer: placed_event.address.to_string(),
                             initial_value: placed_event.value.to_string(),
@@ -130,17 +280,52 @@ impl Handler for OffersHandlerPipeline {
                             updated_at: value.created_at,
                             created_at: value.created_at,
                             last_tx_digest: value.tx_digest.clone(),
-                        }])
-                        .execute(conn)
+                            coin_type: value.coin_type.clone(),
+                            stale_reason: None,
+                            locked_value: "0".to_string(),
+                            buyer_value: placed_event.value.to_string(),
+                            counter_value: None,
+                        })
+                        .returning(offers::id)
+                        .get_result(conn)
                         .await
                         .map_err(Into::<Error>::into)?;
+
+                    escrow::lock(
+                        conn,
+                        EscrowKind::Offer,
+                        &id.to_string(),
+                        &placed_event.address.to_string(),
+                        placed_event.value,
+                        "offer-placed",
+                        &value.escrow_context(),
+                    )
+                    .await?;
+
+                    Self::record_round(
+                        conn,
+                        value,
+                        id,
+                        &placed_event.address.to_string(),
+                        ActivityRole::Buyer,
+                        "offer",
+                        placed_event.value,
+                    )
+                    .await?;
+
+                    changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                        .await?;
                 }
                 OfferEvent::Cancelled(offer_cancelled) => {
                     let domain_name = convert_domain_name(&offer_cancelled.domain_name);
 
-                    let latest_offer_id =
-                        Self::get_latest_offer_id(conn, &offer_cancelled.address, &domain_name)
-                            .await?;
+                    let latest_offer_id = Self::get_latest_offer_id(
+                        conn,
+                        &offer_cancelled.address,
+                        &domain_name,
+                        &value.coin_type,
+                    )
+                    .await?;
 
                     // Then update if found
                     if let Some(id) = latest_offer_id {
@@ -156,17 +341,35 @@ impl Handler for OffersHandlerPipeline {
                                 status: OfferStatus::Cancelled,
                                 updated_at: value.created_at,
                                 last_tx_digest: value.tx_digest.clone(),
+                                buyer_value: None,
+                                counter_value: None,
                             })
                             .execute(conn)
                             .await?;
+
+                        escrow::release_all(
+                            conn,
+                            EscrowKind::Offer,
+                            &id.to_string(),
+                            "offer-cancelled",
+                            &value.escrow_context(),
+                        )
+                        .await?;
+
+                        changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                            .await?;
                     }
                 }
                 OfferEvent::Accepted(offer_accepted) => {
                     let domain_name = convert_domain_name(&offer_accepted.domain_name);
 
-                    let latest_offer_id =
-                        Self::get_latest_offer_id(conn, &offer_accepted.buyer, &domain_name)
-                            .await?;
+                    let latest_offer_id = Self::get_latest_offer_id(
+                        conn,
+                        &offer_accepted.buyer,
+                        &domain_name,
+                        &value.coin_type,
+                    )
+                    .await?;
 
                     if let Some(id) = latest_offer_id {
                         info!(
@@ -181,17 +384,62 @@ impl Handler for OffersHandlerPipeline {
                                 status: OfferStatus::Accepted,
                                 updated_at: value.created_at,
                                 last_tx_digest: value.tx_digest.clone(),
+                                buyer_value: None,
+                                counter_value: None,
                             })
                             .execute(conn)
                             .await?;
+
+                        Self::record_round(
+                            conn,
+                            value,
+                            id,
+                            &offer_accepted.owner.to_string(),
+                            ActivityRole::Owner,
+                            "accept",
+                            offer_accepted.value,
+                        )
+                        .await?;
+
+                        escrow::release_all(
+                            conn,
+                            EscrowKind::Offer,
+                            &id.to_string(),
+                            "offer-accepted",
+                            &value.escrow_context(),
+                        )
+                        .await?;
+
+                        changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                            .await?;
                     }
+
+                    sales::record_sale(
+                        conn,
+                        Sale {
+                            domain_name,
+                            seller: offer_accepted.owner.to_string(),
+                            buyer: offer_accepted.buyer.to_string(),
+                            price: offer_accepted.value.to_string(),
+                            sale_type: SaleType::Offer,
+                            checkpoint: Some(value.checkpoint as i64),
+                            tx_digest: value.tx_digest.clone(),
+                            created_at: value.created_at,
+                            coin_type: value.coin_type.clone(),
+                        },
+                    )
+                    .await?;
                 }
                 OfferEvent::Declined(offer_declined) => {
                     let domain_name = convert_domain_name(&offer_declined.domain_name);
 
-                    let latest_offer_id =
-                        Self::get_latest_offer_id(conn, &offer_declined.buyer, &domain_name)
-                            .await?;
+                    let latest_offer_id = Self::get_latest_offer_id(
+                        conn,
+                        &offer_declined.buyer,
+                        &domain_name,
+                        &value.coin_type,
+                    )
+                    .await?;
 
                     if let Some(id) = latest_offer_id {
                         info!(
@@ -206,17 +454,46 @@ impl Handler for OffersHandlerPipeline {
                                 status: OfferStatus::Declined,
                                 updated_at: value.created_at,
                                 last_tx_digest: value.tx_digest.clone(),
+                                buyer_value: None,
+                                counter_value: None,
                             })
                             .execute(conn)
                             .await?;
+
+                        Self::record_round(
+                            conn,
+                            value,
+                            id,
+                            &offer_declined.owner.to_string(),
+                            ActivityRole::Owner,
+                            "decline",
+                            offer_declined.value,
+                        )
+                        .await?;
+
+                        escrow::release_all(
+                            conn,
+                            EscrowKind::Offer,
+                            &id.to_string(),
+                            "offer-declined",
+                            &value.escrow_context(),
+                        )
+                        .await?;
+
+                        changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                            .await?;
                     }
                 }
                 OfferEvent::MakeCounterOffer(make_counter_offer) => {
                     let domain_name = convert_domain_name(&make_counter_offer.domain_name);
 
-                    let latest_offer_id =
-                        Self::get_latest_offer_id(conn, &make_counter_offer.buyer, &domain_name)
-                            .await?;
+                    let latest_offer_id = Self::get_latest_offer_id(
+                        conn,
+                        &make_counter_offer.buyer,
+                        &domain_name,
+                        &value.coin_type,
+                    )
+                    .await?;
 
                     if let Some(id) = latest_offer_id {
                         info!(
@@ -231,17 +508,37 @@ impl Handler for OffersHandlerPipeline {
                                 status: OfferStatus::Countered,
                                 updated_at: value.created_at,
                                 last_tx_digest: value.tx_digest.clone(),
+                                buyer_value: None,
+                                counter_value: Some(Some(make_counter_offer.value.to_string())),
                             })
                             .execute(conn)
                             .await?;
+
+                        Self::record_round(
+                            conn,
+                            value,
+                            id,
+                            &make_counter_offer.owner.to_string(),
+                            ActivityRole::Owner,
+                            "counter-offer",
+                            make_counter_offer.value,
+                        )
+                        .await?;
+
+                        changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                            .await?;
                     }
                 }
                 OfferEvent::AcceptCounterOffer(accept_counter_offer) => {
                     let domain_name = convert_domain_name(&accept_counter_offer.domain_name);
 
-                    let latest_offer_id =
-                        Self::get_latest_offer_id(conn, &accept_counter_offer.buyer, &domain_name)
-                            .await?;
+                    let latest_offer_id = Self::get_latest_offer_id(
+                        conn,
+                        &accept_counter_offer.buyer,
+                        &domain_name,
+                        &value.coin_type,
+                    )
+                    .await?;
 
                     if let Some(id) = latest_offer_id {
                         info!(
@@ -256,14 +553,98 @@ impl Handler for OffersHandlerPipeline {
                                 status: OfferStatus::AcceptedCountered,
                                 updated_at: value.created_at,
                                 last_tx_digest: value.tx_digest.clone(),
+                                buyer_value: Some(accept_counter_offer.value.to_string()),
+                                counter_value: None,
                             })
                             .execute(conn)
                             .await?;
+
+                        Self::record_round(
+                            conn,
+                            value,
+                            id,
+                            &accept_counter_offer.buyer.to_string(),
+                            ActivityRole::Buyer,
+                            "accept-counter-offer",
+                            accept_counter_offer.value,
+                        )
+                        .await?;
+
+                        escrow::release_all(
+                            conn,
+                            EscrowKind::Offer,
+                            &id.to_string(),
+                            "counter-offer-accepted",
+                            &value.escrow_context(),
+                        )
+                        .await?;
+
+                        changes::offer_changed(conn, id, Some(&value.tx_digest), value.checkpoint)
+                            .await?;
+
+                        // The seller is only known from the counter offer made by the owner
+                        let seller: Option<String> = offers::table
+                            .select(offers::owner)
+                            .filter(offers::id.eq(id))
+                            .first(conn)
+                            .await?;
+
+                        match seller {
+                            Some(seller) => {
+                                sales::record_sale(
+                                    conn,
+                                    Sale {
+                                        domain_name,
+                                        seller,
+                                        buyer: accept_counter_offer.buyer.to_string(),
+                                        price: accept_counter_offer.value.to_string(),
+                                        sale_type: SaleType::CounterOffer,
+                                        checkpoint: Some(value.checkpoint as i64),
+                                        tx_digest: value.tx_digest.clone(),
+                                        created_at: value.created_at,
+                                        coin_type: value.coin_type.clone(),
+                                    },
+                                )
+                                .await?;
+                            }
+                            None => {
+                                warn!(
+                                    "Could not find seller of countered offer for domain {} and buyer {}",
+                                    domain_name, accept_counter_offer.buyer
+                                );
+                            }
+                        }
                     }
                 }
             }
+
+            domains::refresh_offers(
+                conn,
+                &value.event.domain_name(),
+                &value.coin_type,
+                value.created_at,
+            )
+            .await?;
+            Self::record_activity(conn, value).await?;
+            Self::publish_event(conn, value).await?;
+        }
+
+        // Offers indexed after their domain was sold, transferred or expired are stale right away
+        if let Some(last) = batch.iter().max_by_key(|value| value.checkpoint) {
+            let domain_names: BTreeSet<String> =
+                batch.iter().map(|value| value.event.domain_name()).collect();
+            for domain_name in domain_names {
+                let (now, checkpoint) = (last.created_at, last.checkpoint);
+                stale_offers::invalidate_stale(conn, &domain_name, now, checkpoint).await?;
+            }
         }
 
+        let mut periods = TouchedPeriods::default();
+        for value in batch {
+            periods.add(value.epoch, value.created_at, true);
+        }
+        stats::refresh(conn, &periods).await?;
+
         Ok(len)
     }
 }
@@ -278,38 +659,38 @@ impl OffersHandlerPipeline {
     fn process_event(&self, event: &Event) -> Result<Option<OfferEvent>> {
         let event_type = event.type_.to_string();
         if event_type.starts_with(&self.contract_package_id) {
-            if event_type.ends_with("::OfferPlacedEvent") {
+            if event_name(event) == "OfferPlacedEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let offer_event: OfferPlacedEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(OfferEvent::Placed(offer_event)));
-            } else if event_type.ends_with("::OfferCancelledEvent") {
+            } else if event_name(event) == "OfferCancelledEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let cancel_event: OfferCancelledEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(OfferEvent::Cancelled(cancel_event)));
-            } else if event_type.ends_with("::OfferAcceptedEvent") {
+            } else if event_name(event) == "OfferAcceptedEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let accepted_event: OfferAcceptedEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(OfferEvent::Accepted(accepted_event)));
-            } else if event_type.ends_with("::OfferDeclinedEvent") {
+            } else if event_name(event) == "OfferDeclinedEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let declined_event: OfferDeclinedEvent = try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(OfferEvent::Declined(declined_event)));
-            } else if event_type.ends_with("::MakeCounterOfferEvent") {
+            } else if event_name(event) == "MakeCounterOfferEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let make_counter_offer_event: MakeCounterOfferEvent =
                     try_deserialize_event(&event.contents)?;
 
                 return Ok(Some(OfferEvent::MakeCounterOffer(make_counter_offer_event)));
-            } else if event_type.ends_with("::AcceptCounterOfferEvent") {
+            } else if event_name(event) == "AcceptCounterOfferEvent" {
                 info!("Found Offer event: {} ", event_type);
 
                 let accept_counter_offer_event: AcceptCounterOfferEvent =
@@ -324,15 +705,210 @@ impl OffersHandlerPipeline {
         Ok(None)
     }
 
+    // Record the event for every address involved and refresh what the buyer has committed
+    async fn record_activity<'a>(conn: &mut Connection<'a>, value: &OfferValue) -> Result<()> {
+        let activity = match &value.event {
+            OfferEvent::Placed(event) => vec![value.activity(
+                event.address.to_string(),
+                ActivityRole::Buyer,
+                "offer-placed",
+                None,
+                event.value,
+            )],
+            OfferEvent::Cancelled(event) => vec![value.activity(
+                event.address.to_string(),
+                ActivityRole::Buyer,
+                "offer-cancelled",
+                None,
+                event.value,
+            )],
+            OfferEvent::Accepted(event) => vec![
+                value.activity(
+                    event.buyer.to_string(),
+                    ActivityRole::Buyer,
+                    "offer-accepted",
+                    Some(event.owner.to_string()),
+                    event.value,
+                ),
+                value.activity(
+                    event.owner.to_string(),
+                    ActivityRole::Owner,
+                    "offer-accepted",
+                    Some(event.buyer.to_string()),
+                    event.value,
+                ),
+            ],
+            OfferEvent::Declined(event) => vec![
+                value.activity(
+                    event.buyer.to_string(),
+                    ActivityRole::Buyer,
+                    "offer-declined",
+                    Some(event.owner.to_string()),
+                    event.value,
+                ),
+                value.activity(
+                    event.owner.to_string(),
+                    ActivityRole::Owner,
+                    "offer-declined",
+                    Some(event.buyer.to_string()),
+                    event.value,
+                ),
+            ],
+            OfferEvent::MakeCounterOffer(event) => vec![
+                value.activity(
+                    event.buyer.to_string(),
+                    ActivityRole::Buyer,
+                    "counter-offer-made",
+                    Some(event.owner.to_string()),
+                    event.value,
+                ),
+                value.activity(
+                    event.owner.to_string(),
+                    ActivityRole::Owner,
+                    "counter-offer-made",
+                    Some(event.buyer.to_string()),
+                    event.value,
+                ),
+            ],
+            OfferEvent::AcceptCounterOffer(event) => {
+                // The event doesn't carry the owner, it is only known from the counter offer
+                let owner = Self::latest_offer(conn, value)
+                    .await?
+                    .and_then(|(_, owner)| owner);
+
+                let mut activity = vec![value.activity(
+                    event.buyer.to_string(),
+                    ActivityRole::Buyer,
+                    "counter-offer-accepted",
+                    owner.clone(),
+                    event.value,
+                )];
+                if let Some(owner) = owner {
+                    activity.push(value.activity(
+                        owner,
+                        ActivityRole::Owner,
+                        "counter-offer-accepted",
+                        Some(event.buyer.to_string()),
+                        event.value,
+                    ));
+                }
+                activity
+            }
+        };
+
+        accounts::record_activity(conn, &activity).await?;
+        accounts::refresh_open_commitments(
+            conn,
+            &value.event.buyer().to_string(),
+            &value.coin_type,
+            value.created_at,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // The id and owner of the offer the event applied to, the most recently updated one of its
+    // buyer for the domain and coin
+    async fn latest_offer<'a>(
+        conn: &mut Connection<'a>,
+        value: &OfferValue,
+    ) -> Result<Option<(i32, Option<String>)>> {
+        Ok(offers::table
+            .select((offers::id, offers::owner))
+            .filter(offers::domain_name.eq(value.event.domain_name()))
+            .filter(offers::buyer.eq(value.event.buyer().to_string()))
+            .filter(offers::coin_type.eq(&value.coin_type))
+            .order((offers::updated_at.desc(), offers::id.desc()))
+            .first(conn)
+            .await
+            .optional()?)
+    }
+
+    // Publish the event with the offer it applied to, for the live feed and webhooks
+    async fn publish_event<'a>(conn: &mut Connection<'a>, value: &OfferValue) -> Result<()> {
+        let buyer = value.event.buyer().to_string();
+
+        let (offer_id, offer_owner) = Self::latest_offer(conn, value).await?.unzip();
+
+        let (event_type, owner, amount) = match &value.event {
+            OfferEvent::Placed(event) => ("offer-placed", None, event.value),
+            OfferEvent::Cancelled(event) => ("offer-cancelled", None, event.value),
+            OfferEvent::Accepted(event) => {
+                ("offer-accepted", Some(event.owner.to_string()), event.value)
+            }
+            OfferEvent::Declined(event) => {
+                ("offer-declined", Some(event.owner.to_string()), event.value)
+            }
+            OfferEvent::MakeCounterOffer(event) => {
+                ("counter-offer-made", Some(event.owner.to_string()), event.value)
+            }
+            // The event doesn't carry the owner, it is only known from the counter offer
+            OfferEvent::AcceptCounterOffer(event) => {
+                ("counter-offer-accepted", offer_owner.flatten(), event.value)
+            }
+        };
+
+        let data = serde_json::json!({
+            "offer_id": offer_id,
+            "buyer": buyer,
+            "owner": owner,
+            "amount": amount.to_string(),
+        });
+        let addresses = [Some(buyer), owner].into_iter().flatten().collect();
+
+        market_events::publish(conn, &value.market_event(event_type, addresses, data)).await?;
+
+        Ok(())
+    }
+
+    // Record a step of the negotiation of an offer, numbered after the steps before it
+    async fn record_round<'a>(
+        conn: &mut Connection<'a>,
+        value: &OfferValue,
+        offer_id: i32,
+        proposer: &str,
+        proposer_role: ActivityRole,
+        action: &str,
+        amount: u64,
+    ) -> Result<()> {
+        let previous_rounds: i64 = negotiation_rounds::table
+            .filter(negotiation_rounds::offer_id.eq(offer_id))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        diesel::insert_into(negotiation_rounds::table)
+            .values(NegotiationRound {
+                offer_id,
+                round: previous_rounds as i32 + 1,
+                domain_name: value.event.domain_name(),
+                proposer: proposer.to_string(),
+                proposer_role,
+                action: action.to_string(),
+                amount: amount.to_string(),
+                coin_type: value.coin_type.clone(),
+                checkpoint: value.checkpoint as i64,
+                tx_digest: value.tx_digest.clone(),
+                created_at: value.created_at,
+            })
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
     async fn get_latest_offer_id<'a>(
         conn: &mut Connection<'a>,
         buyer: &SuiAddress,
         domain_name: &String,
+        coin_type: &String,
     ) -> Result<Option<i32>> {
         let result = offers::table
             .select(offers::id)
             .filter(offers::domain_name.eq(&domain_name))
             .filter(offers::buyer.eq(&buyer.to_string()))
+            .filter(offers::coin_type.eq(coin_type))
             .order(offers::updated_at.desc())
             .first(conn)
             .await
@@ -0,0 +1,256 @@
+use crate::dry_run::DryRunValue;
+use crate::models::{OwnerKind, RegistrationOwner};
+use crate::projections::stale_offers;
+use crate::registry::{self, SuinsRegistration};
+use crate::schema::registration_owners;
+use anyhow::Context;
+use async_trait::async_trait;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::{error, info};
+use std::collections::HashSet;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::sequential::Handler;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_indexer_alt_framework::postgres::{Connection, Db};
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use sui_indexer_alt_framework::FieldCount;
+use sui_indexer_alt_framework::Result;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::object::{Object, Owner};
+
+#[derive(FieldCount, Clone)]
+pub struct RegistrationOwnerValue {
+    registration: SuinsRegistration,
+    owner_kind: OwnerKind,
+    owner: Option<String>,
+    created_at: DateTime<Utc>,
+    tx_digest: String,
+    checkpoint: u64,
+}
+
+impl DryRunValue for RegistrationOwnerValue {
+    // Whether the owner change is a transfer depends on the indexed holder
+    fn to_json(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "tx_digest": self.tx_digest,
+            "timestamp": self.created_at.to_rfc3339(),
+            "transition": {
+                "table": "registration_owners",
+                "nft_id": self.registration.id.to_string(),
+                "domain_name": self.registration.domain_name,
+                "owner_kind": self.owner_kind,
+                "owner": self.owner,
+            },
+        }))
+    }
+}
+
+pub struct RegistrationOwnersHandlerPipeline {
+    suins_package_id: String,
+}
+
+impl Processor for RegistrationOwnersHandlerPipeline {
+    const NAME: &'static str = "registration_owners";
+
+    type Value = RegistrationOwnerValue;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let timestamp_ms: u64 = checkpoint.checkpoint_summary.timestamp_ms.into();
+        let timestamp_i64 =
+            i64::try_from(timestamp_ms).context("Timestamp too large to convert to i64")?;
+        let created_at: DateTime<Utc> =
+            DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+
+        let mut values = Vec::new();
+
+        for tx in &checkpoint.transactions {
+            let tx_digest = tx.transaction.digest().to_string();
+
+            let mut changes = Vec::new();
+
+            // Created and transferred registrations show up with their new owner
+            for object in &tx.output_objects {
+                if let Some(registration) = self.process_object(object) {
+                    let (owner_kind, owner) = owner(&object.owner);
+                    changes.push((registration, owner_kind, owner));
+                }
+            }
+
+            // Wrapped and deleted registrations are only found in the inputs
+            let wrapped: HashSet<_> = tx
+                .effects
+                .wrapped()
+                .into_iter()
+                .map(|(object_id, _, _)| object_id)
+                .collect();
+            let deleted: HashSet<_> = tx
+                .effects
+                .deleted()
+                .into_iter()
+                .map(|(object_id, _, _)| object_id)
+                .collect();
+
+            for object in &tx.input_objects {
+                let owner_kind = if wrapped.contains(&object.id()) {
+                    OwnerKind::Wrapped
+                } else if deleted.contains(&object.id()) {
+                    OwnerKind::Deleted
+                } else {
+                    continue;
+                };
+
+                if let Some(registration) = self.process_object(object) {
+                    changes.push((registration, owner_kind, None));
+                }
+            }
+
+            values.extend(changes.into_iter().map(|(registration, owner_kind, owner)| {
+                RegistrationOwnerValue {
+                    registration,
+                    owner_kind,
+                    owner,
+                    created_at,
+                    tx_digest: tx_digest.clone(),
+                    checkpoint: checkpoint_seq,
+                }
+            }));
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl Handler for RegistrationOwnersHandlerPipeline {
+    type Store = Db;
+    type Batch = Vec<Self::Value>;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        batch.extend(values);
+    }
+
+    async fn commit<'a>(batch: &Self::Batch, conn: &mut Connection<'a>) -> Result<usize> {
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let len = batch.len();
+
+        info!("Processing {} registration ownership changes", len);
+
+        for value in batch {
+            let registration = &value.registration;
+            let nft_id = registration.id.to_string();
+
+            info!(
+                "Registration {} of domain {} is now {:?} owned by {:?}",
+                nft_id, registration.domain_name, value.owner_kind, value.owner
+            );
+
+            let existing = registration_owners::table
+                .find(&nft_id)
+                .select(RegistrationOwner::as_select())
+                .first(conn)
+                .await
+                .optional()?;
+
+            let previous_holder = existing.as_ref().and_then(|existing| existing.holder.clone());
+            let previous_transferred_at = existing.and_then(|existing| existing.transferred_at);
+
+            // Wrapping the registration, e.g. into an auction, or moving it into another object
+            // keeps its holder. Only a different address taking it over is a transfer.
+            let (holder, transferred_at) = match (value.owner_kind, &value.owner) {
+                (OwnerKind::Address, Some(owner)) => {
+                    let transferred = previous_holder
+                        .as_ref()
+                        .is_some_and(|previous_holder| previous_holder != owner);
+                    let transferred_at = if transferred {
+                        Some(value.created_at)
+                    } else {
+                        previous_transferred_at
+                    };
+                    (Some(owner.clone()), transferred_at)
+                }
+                _ => (previous_holder, previous_transferred_at),
+            };
+
+            let registration_owner = RegistrationOwner {
+                nft_id,
+                domain_name: registration.domain_name.clone(),
+                owner_kind: value.owner_kind,
+                owner: value.owner.clone(),
+                expiration_timestamp_ms: i64::try_from(registration.expiration_timestamp_ms)
+                    .context("Expiration too large to convert to i64")?,
+                checkpoint: value.checkpoint as i64,
+                last_tx_digest: value.tx_digest.clone(),
+                created_at: value.created_at,
+                updated_at: value.created_at,
+                holder,
+                transferred_at,
+            };
+
+            diesel::insert_into(registration_owners::table)
+                .values(&registration_owner)
+                .on_conflict(registration_owners::nft_id)
+                .do_update()
+                .set((
+                    registration_owners::owner_kind.eq(registration_owner.owner_kind),
+                    registration_owners::owner.eq(&registration_owner.owner),
+                    registration_owners::expiration_timestamp_ms
+                        .eq(registration_owner.expiration_timestamp_ms),
+                    registration_owners::checkpoint.eq(registration_owner.checkpoint),
+                    registration_owners::last_tx_digest.eq(&registration_owner.last_tx_digest),
+                    registration_owners::updated_at.eq(registration_owner.updated_at),
+                    registration_owners::holder.eq(&registration_owner.holder),
+                    registration_owners::transferred_at.eq(registration_owner.transferred_at),
+                ))
+                .execute(conn)
+                .await?;
+
+            if registration_owner.transferred_at != previous_transferred_at {
+                stale_offers::invalidate_stale(
+                    conn,
+                    &registration_owner.domain_name,
+                    value.created_at,
+                    value.checkpoint,
+                )
+                .await?;
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+impl RegistrationOwnersHandlerPipeline {
+    pub fn new(suins_package_id: String) -> Self {
+        Self { suins_package_id }
+    }
+
+    fn process_object(&self, object: &Object) -> Option<SuinsRegistration> {
+        match registry::try_registration(object, &self.suins_package_id) {
+            Ok(registration) => registration,
+            Err(e) => {
+                // Should not be reached
+                error!("Error processing registration {}: {}", object.id(), e);
+                panic!("Error processing registration {}: {}", object.id(), e);
+            }
+        }
+    }
+}
+
+fn owner(owner: &Owner) -> (OwnerKind, Option<String>) {
+    match owner {
+        Owner::AddressOwner(address) => (OwnerKind::Address, Some(address.to_string())),
+        Owner::ObjectOwner(address) => (OwnerKind::Object, Some(address.to_string())),
+        Owner::Shared { .. } => (OwnerKind::Shared, None),
+        Owner::Immutable => (OwnerKind::Immutable, None),
+        Owner::ConsensusAddressOwner { owner, .. } => {
+            (OwnerKind::Address, Some(owner.to_string()))
+        }
+    }
+}
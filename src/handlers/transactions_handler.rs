@@ -0,0 +1,141 @@
+use crate::dry_run::DryRunValue;
+use crate::models::Transaction;
+use crate::registry;
+use crate::schema::transactions;
+use anyhow::Context;
+use async_trait::async_trait;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel_async::RunQueryDsl;
+use log::info;
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::concurrent::Handler;
+use sui_indexer_alt_framework::pipeline::Processor;
+use sui_indexer_alt_framework::postgres::{Connection, Db};
+use sui_indexer_alt_framework::types::full_checkpoint_content::{
+    CheckpointData, CheckpointTransaction,
+};
+use sui_indexer_alt_framework::Result;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::execution_status::ExecutionStatus;
+use sui_types::transaction::{Command, TransactionDataAPI, TransactionKind};
+
+impl DryRunValue for Transaction {
+    fn to_json(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "transaction": self }))
+    }
+}
+
+pub struct TransactionsHandlerPipeline {
+    contract_package_id: String,
+}
+
+impl Processor for TransactionsHandlerPipeline {
+    const NAME: &'static str = "transactions";
+
+    type Value = Transaction;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let timestamp_ms: u64 = checkpoint.checkpoint_summary.timestamp_ms.into();
+        let timestamp_i64 =
+            i64::try_from(timestamp_ms).context("Timestamp too large to convert to i64")?;
+        let created_at: DateTime<Utc> =
+            DateTime::<Utc>::from_timestamp_millis(timestamp_i64).context("invalid timestamp")?;
+
+        let mut values = Vec::new();
+
+        for tx in &checkpoint.transactions {
+            let event_count = tx
+                .events
+                .iter()
+                .flat_map(|events| &events.data)
+                .filter(|event| event.type_.to_string().starts_with(&self.contract_package_id))
+                .count();
+
+            // Only transactions of the marketplace are recorded. Failed transactions have no
+            // events, so they are matched by what they call and the objects they use.
+            if event_count == 0 && !uses_package(tx, &self.contract_package_id) {
+                continue;
+            }
+
+            let data = tx.transaction.transaction_data();
+            let gas_cost = tx.effects.gas_cost_summary();
+
+            let (status, error) = match tx.effects.status() {
+                ExecutionStatus::Success => ("success", None),
+                ExecutionStatus::Failure { error, command } => {
+                    ("failure", Some(format!("{:?} in command {:?}", error, command)))
+                }
+            };
+
+            values.push(Transaction {
+                tx_digest: tx.transaction.digest().to_string(),
+                sender: data.sender().to_string(),
+                gas_budget: data.gas_budget().to_string(),
+                gas_price: data.gas_price().to_string(),
+                computation_cost: gas_cost.computation_cost.to_string(),
+                storage_cost: gas_cost.storage_cost.to_string(),
+                storage_rebate: gas_cost.storage_rebate.to_string(),
+                non_refundable_storage_fee: gas_cost.non_refundable_storage_fee.to_string(),
+                status: status.to_string(),
+                error,
+                event_count: event_count as i32,
+                checkpoint: checkpoint.checkpoint_summary.sequence_number as i64,
+                epoch: checkpoint.checkpoint_summary.epoch as i64,
+                created_at,
+            });
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl Handler for TransactionsHandlerPipeline {
+    type Store = Db;
+
+    async fn commit<'a>(values: &[Self::Value], conn: &mut Connection<'a>) -> Result<usize> {
+        if values.is_empty() {
+            return Ok(0);
+        }
+
+        info!("Inserting {} transactions", values.len());
+
+        // Checkpoints can be committed more than once, e.g. after a restart
+        let count = diesel::insert_into(transactions::table)
+            .values(values)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+
+        Ok(count)
+    }
+}
+
+impl TransactionsHandlerPipeline {
+    pub fn new(contract_package_id: String) -> Self {
+        Self {
+            contract_package_id,
+        }
+    }
+}
+
+/// Whether the transaction calls a function of the package, or has an object of one of its types
+/// as input. Types are matched by their defining package, so calls to an upgraded version of the
+/// package are only matched through its objects.
+pub fn uses_package(tx: &CheckpointTransaction, contract_package_id: &str) -> bool {
+    let calls_package = match tx.transaction.transaction_data().kind() {
+        TransactionKind::ProgrammableTransaction(programmable) => {
+            programmable.commands.iter().any(|command| match command {
+                Command::MoveCall(call) => call.package.to_string() == contract_package_id,
+                _ => false,
+            })
+        }
+        _ => false,
+    };
+
+    calls_package
+        || tx.input_objects.iter().any(|object| {
+            registry::struct_tag(object)
+                .is_some_and(|tag| tag.to_string().starts_with(contract_package_id))
+        })
+}
@@ -1,8 +1,19 @@
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 
+pub mod categorize;
+pub mod checkpoints;
+pub mod decode;
+pub mod dry_run;
+pub mod export;
+pub mod feed;
 pub mod handlers;
 pub mod models;
+pub mod projections;
+pub mod record;
+pub mod registry;
 pub mod schema;
+pub mod sinks;
+pub mod webhooks;
 pub mod events;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -1,42 +1,99 @@
+use anyhow::Context;
 use clap::Parser;
 use log::info;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use sui_indexer_alt_framework::cluster;
 use sui_indexer_alt_framework::cluster::IndexerCluster;
 use sui_indexer_alt_framework::pipeline::concurrent::ConcurrentConfig;
 use sui_indexer_alt_framework::pipeline::sequential::SequentialConfig;
-use sui_indexer_alt_framework::postgres::DbArgs;
+use sui_indexer_alt_framework::postgres::{Db, DbArgs};
 use suins_indexer::handlers::auctions_handler::AuctionsHandlerPipeline;
+use suins_indexer::handlers::name_records_handler::NameRecordsHandlerPipeline;
 use suins_indexer::handlers::offer_events_handler::OfferEventsHandlerPipeline;
 use suins_indexer::handlers::offers_handler::OffersHandlerPipeline;
+use suins_indexer::handlers::registration_owners_handler::RegistrationOwnersHandlerPipeline;
+use suins_indexer::handlers::transactions_handler::TransactionsHandlerPipeline;
+use suins_indexer::categorize;
+use suins_indexer::decode::{self, DecodeArgs};
+use suins_indexer::dry_run::{self, DryRunArgs};
+use suins_indexer::export::{self, ExportArgs};
+use suins_indexer::feed::{self, FeedArgs};
+use suins_indexer::projections::{domains, escrow, stats};
+use suins_indexer::record::{self, RecordArgs};
+use suins_indexer::sinks::{self, EventSink, JsonlSink, SinkArgs};
+use suins_indexer::webhooks::{self, DeliveryArgs};
 use suins_indexer::MIGRATIONS;
 use url::Url;
 
 #[derive(clap::Parser, Debug)]
 struct AppArgs {
+    /// Required to index and by every command but `dry-run`, `decode` and `record`
     #[clap(long, env = "DATABASE_URL")]
-    database_url: Url,
+    database_url: Option<Url>,
 
     #[clap(long, env = "DATABASE_TLS_CA_CERT")]
     database_tls_ca_cert: Option<String>,
 
+    /// Package of the marketplace contract. Required to index, `dry-run` and `record`
     #[clap(long, env = "CONTRACT_PACKAGE_ID")]
-    contract_package_id: String,
+    contract_package_id: Option<String>,
+
+    /// Package of the SuiNS core registry. Registration state is only indexed when it's set.
+    ///
+    /// Must be the original package ID, as a 0x prefixed 64 hex digit address: registry types keep
+    /// the ID of the package that first defined them across upgrades, so any other ID matches no
+    /// objects and nothing gets indexed
+    #[clap(long, env = "SUINS_PACKAGE_ID")]
+    suins_package_id: Option<String>,
+
+    /// File with one word per line, used to tag domains that are dictionary words
+    #[clap(long, env = "WORDLIST_PATH")]
+    wordlist_path: Option<PathBuf>,
+
+    /// Directory to also write every published market event to, as JSON lines
+    #[clap(long, env = "JSONL_SINK_DIR")]
+    jsonl_sink_dir: Option<PathBuf>,
+
+    /// Size after which the JSON lines sink starts a new file
+    #[clap(long, env = "JSONL_SINK_MAX_BYTES", default_value_t = 64 * 1024 * 1024)]
+    jsonl_sink_max_bytes: u64,
+
+    #[clap(flatten)]
+    sink_args: SinkArgs,
 
     #[clap(flatten)]
     cluster_args: cluster::Args,
-}
 
-#[tokio::main]
-async fn main() -> Result<(), anyhow::Error> {
-    dotenvy::dotenv()?;
-    env_logger::init();
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-    let args = AppArgs::parse();
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Rebuild the daily and per-epoch market statistics from the tables indexed so far
+    BackfillStats,
+    /// Recompute the category tags of every domain, e.g. after the wordlist changed
+    CategorizeDomains,
+    /// Check that the funds tracked on offers and auctions match the escrow implied by events
+    ReconcileEscrow,
+    /// Deliver the webhook outbox to the subscribed endpoints, retrying failed deliveries
+    DeliverWebhooks(DeliveryArgs),
+    /// Stream the committed marketplace events to clients over Server-Sent Events
+    ServeFeed(FeedArgs),
+    /// Export the raw events, sales and state history to Parquet, resuming after the last export
+    Export(ExportArgs),
+    /// Print what the pipelines decode from a range of checkpoints, without touching the database
+    DryRun(DryRunArgs),
+    /// Decode the BCS contents of a marketplace event, e.g. copied from a deserialization error
+    Decode(DecodeArgs),
+    /// Save the checkpoints with marketplace events to a directory, e.g. as test fixtures
+    Record(RecordArgs),
+}
 
-    let mut db_args = DbArgs::default();
-    if let Some(cert_content) = args.database_tls_ca_cert {
+fn db_args(database_tls_ca_cert: Option<String>) -> Result<DbArgs, anyhow::Error> {
+    if let Some(cert_content) = database_tls_ca_cert {
         if !cert_content.is_empty() {
             let cert_dir = PathBuf::from("./certificates");
             fs::create_dir_all(&cert_dir)?;
@@ -44,21 +101,127 @@ async fn main() -> Result<(), anyhow::Error> {
             let cert_path = cert_dir.join("ca-cert.crt");
             fs::write(&cert_path, cert_content)?;
 
-            db_args = DbArgs {
+            return Ok(DbArgs {
                 tls_verify_cert: true,
                 tls_ca_cert_path: Some(cert_path),
                 ..DbArgs::default()
-            };
+            });
+        }
+    }
+
+    Ok(DbArgs::default())
+}
+
+fn require_contract_package_id(contract_package_id: Option<&str>) -> Result<&str, anyhow::Error> {
+    contract_package_id.context("--contract-package-id or CONTRACT_PACKAGE_ID is required")
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // The environment may be set without a .env file
+    dotenvy::dotenv().ok();
+    env_logger::init();
+
+    let args = AppArgs::parse();
+
+    let db_args = db_args(args.database_tls_ca_cert)?;
+
+    if let Some(wordlist_path) = &args.wordlist_path {
+        categorize::load_wordlist(wordlist_path)?;
+    }
+
+    // Commands that don't need the database
+    match &args.command {
+        Some(Command::DryRun(dry_run_args)) => {
+            dry_run::run(
+                dry_run_args,
+                require_contract_package_id(args.contract_package_id.as_deref())?,
+                args.suins_package_id.as_deref(),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(Command::Decode(decode_args)) => {
+            decode::run(decode_args)?;
+            return Ok(());
         }
+        Some(Command::Record(record_args)) => {
+            record::run(
+                record_args,
+                require_contract_package_id(args.contract_package_id.as_deref())?,
+                args.suins_package_id.as_deref(),
+            )
+            .await?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let database_url = args
+        .database_url
+        .context("--database-url or DATABASE_URL is required")?;
+
+    if let Some(command) = args.command {
+        let db = Db::for_write(database_url, db_args).await?;
+        let mut conn = db.connect().await?;
+
+        match command {
+            Command::BackfillStats => {
+                info!("Backfilling market statistics");
+                stats::backfill(&mut conn).await?;
+            }
+            Command::CategorizeDomains => {
+                info!("Categorizing domains");
+                domains::categorize_all(&mut conn).await?;
+            }
+            Command::ReconcileEscrow => {
+                info!("Reconciling escrow");
+                let mismatches = escrow::reconcile(&mut conn).await?;
+                if mismatches > 0 {
+                    anyhow::bail!("Found {} escrow mismatches", mismatches);
+                }
+            }
+            Command::DeliverWebhooks(delivery_args) => {
+                info!("Delivering webhooks");
+                webhooks::run(&mut conn, &delivery_args).await?;
+            }
+            Command::ServeFeed(feed_args) => {
+                feed::serve(db.clone(), feed_args).await?;
+            }
+            Command::Export(export_args) => {
+                info!("Exporting to {}", export_args.output.display());
+                export::run(&mut conn, &export_args).await?;
+            }
+            Command::DryRun(_) | Command::Decode(_) | Command::Record(_) => {
+                unreachable!("handled without a database")
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut event_sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    if let Some(jsonl_sink_dir) = args.jsonl_sink_dir {
+        let sink = JsonlSink::new(jsonl_sink_dir, args.jsonl_sink_max_bytes)?;
+        event_sinks.push(Arc::new(sink));
     }
 
+    // Sinks read the committed events, next to the pipelines
+    if !event_sinks.is_empty() {
+        let db = Db::for_write(database_url.clone(), db_args.clone()).await?;
+        tokio::spawn(sinks::run(db, event_sinks, args.sink_args));
+    }
+
+    let contract_package_id =
+        require_contract_package_id(args.contract_package_id.as_deref())?.to_string();
+
     info!(
         "Starting indexer with Contract package ID: {}",
-        args.contract_package_id
+        contract_package_id
     );
 
     let mut indexer = IndexerCluster::builder()
-        .with_database_url(args.database_url)
+        .with_database_url(database_url)
         .with_db_args(db_args)
         .with_args(args.cluster_args)
         .with_migrations(&MIGRATIONS)
@@ -70,7 +233,15 @@ async fn main() -> Result<(), anyhow::Error> {
     // Process all offer events, in any order, and save them to database to separate tables
     indexer
         .concurrent_pipeline(
-            OfferEventsHandlerPipeline::new(args.contract_package_id.clone()),
+            OfferEventsHandlerPipeline::new(contract_package_id.clone()),
+            ConcurrentConfig::default(),
+        )
+        .await?;
+
+    // Record sender and gas of every marketplace transaction, in any order
+    indexer
+        .concurrent_pipeline(
+            TransactionsHandlerPipeline::new(contract_package_id.clone()),
             ConcurrentConfig::default(),
         )
         .await?;
@@ -78,7 +249,7 @@ async fn main() -> Result<(), anyhow::Error> {
     // Process all offer events in order and save up to date offer information in database
     indexer
         .sequential_pipeline(
-            OffersHandlerPipeline::new(args.contract_package_id.clone()),
+            OffersHandlerPipeline::new(contract_package_id.clone()),
             SequentialConfig::default(),
         )
         .await?;
@@ -86,11 +257,31 @@ async fn main() -> Result<(), anyhow::Error> {
     // Process all auction & bid events in order and save up to date offer information in database
     indexer
         .sequential_pipeline(
-            AuctionsHandlerPipeline::new(args.contract_package_id),
+            AuctionsHandlerPipeline::new(contract_package_id),
             SequentialConfig::default(),
         )
         .await?;
 
+    // Process all changes of the SuiNS registry in order and save the registration state of domains
+    if let Some(suins_package_id) = args.suins_package_id {
+        info!("Indexing SuiNS registry of package ID: {}", suins_package_id);
+
+        indexer
+            .sequential_pipeline(
+                NameRecordsHandlerPipeline::new(suins_package_id.clone()),
+                SequentialConfig::default(),
+            )
+            .await?;
+
+        // Follow the registration NFTs to know the current holder of every name
+        indexer
+            .sequential_pipeline(
+                RegistrationOwnersHandlerPipeline::new(suins_package_id),
+                SequentialConfig::default(),
+            )
+            .await?;
+    }
+
     let _ = indexer.run().await?.await;
 
     Ok(())
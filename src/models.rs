@@ -1,11 +1,11 @@
 use crate::schema::*;
-use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::internal::derives::multiconnection::chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use diesel::{AsExpression, FromSqlRow};
 use serde::{Deserialize, Serialize};
 use sui_indexer_alt_framework::FieldCount;
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = offer_placed)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct OfferPlaced {
@@ -14,9 +14,11 @@ pub struct OfferPlaced {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = offer_cancelled)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct OfferCancelled {
@@ -25,9 +27,11 @@ pub struct OfferCancelled {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = offer_accepted)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct OfferAccepted {
@@ -37,9 +41,11 @@ pub struct OfferAccepted {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = offer_declined)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct OfferDeclined {
@@ -49,9 +55,11 @@ pub struct OfferDeclined {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = make_counter_offer)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct MakeCounterOffer {
@@ -61,9 +69,11 @@ pub struct MakeCounterOffer {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
-#[derive(Insertable, Debug, FieldCount, Clone)]
+#[derive(Insertable, Queryable, Selectable, Debug, FieldCount, Clone, Serialize)]
 #[diesel(table_name = accept_counter_offer)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct AcceptCounterOffer {
@@ -72,6 +82,8 @@ pub struct AcceptCounterOffer {
     pub value: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub coin_type: String,
+    pub checkpoint: Option<i64>,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -86,6 +98,14 @@ pub struct Offer {
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub last_tx_digest: String,
+    pub coin_type: String,
+    // Why the offer can no longer be accepted, for stale offers
+    pub stale_reason: Option<String>,
+    // Funds of the buyer held by the offer, until it's cancelled, declined or accepted
+    pub locked_value: String,
+    // Latest value proposed by the buyer, and the owner's latest counter to it
+    pub buyer_value: String,
+    pub counter_value: Option<String>,
 }
 
 #[derive(Debug, Clone, AsChangeset, Serialize, Deserialize)]
@@ -96,12 +116,53 @@ pub struct UpdateOffer {
     pub status: OfferStatus,
     pub updated_at: DateTime<Utc>,
     pub last_tx_digest: String,
+    pub buyer_value: Option<String>,
+    pub counter_value: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = offers_history)]
+pub struct OfferHistory {
+    pub offer_id: i32,
+    pub domain_name: String,
+    pub buyer: String,
+    pub initial_value: String,
+    pub value: String,
+    pub owner: Option<String>,
+    pub status: OfferStatus,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub last_tx_digest: String,
+    pub coin_type: String,
+    pub stale_reason: Option<String>,
+    pub locked_value: String,
+    pub buyer_value: String,
+    pub counter_value: Option<String>,
+    pub valid_from_checkpoint: i64,
+    pub valid_to_checkpoint: Option<i64>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = negotiation_rounds)]
+pub struct NegotiationRound {
+    pub offer_id: i32,
+    pub round: i32,
+    pub domain_name: String,
+    pub proposer: String,
+    pub proposer_role: ActivityRole,
+    pub action: String,
+    pub amount: String,
+    pub coin_type: String,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
 )]
 #[diesel(sql_type = crate::schema::sql_types::Offerstatus)]
+#[serde(rename_all = "kebab-case")]
 pub enum OfferStatus {
     Placed,
     Cancelled,
@@ -109,6 +170,40 @@ pub enum OfferStatus {
     Declined,
     Countered,
     AcceptedCountered,
+    Stale,
+}
+
+impl OfferStatus {
+    /// Name of the variant in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfferStatus::Placed => "placed",
+            OfferStatus::Cancelled => "cancelled",
+            OfferStatus::Accepted => "accepted",
+            OfferStatus::Declined => "declined",
+            OfferStatus::Countered => "countered",
+            OfferStatus::AcceptedCountered => "accepted-countered",
+            OfferStatus::Stale => "stale",
+        }
+    }
+}
+
+impl std::str::FromStr for OfferStatus {
+    type Err = anyhow::Error;
+
+    /// Parses the name of the variant in the database
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "placed" => Ok(OfferStatus::Placed),
+            "cancelled" => Ok(OfferStatus::Cancelled),
+            "accepted" => Ok(OfferStatus::Accepted),
+            "declined" => Ok(OfferStatus::Declined),
+            "countered" => Ok(OfferStatus::Countered),
+            "accepted-countered" => Ok(OfferStatus::AcceptedCountered),
+            "stale" => Ok(OfferStatus::Stale),
+            _ => Err(anyhow::anyhow!("unknown offer status {:?}", value)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
@@ -126,6 +221,35 @@ pub struct Auction {
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub last_tx_digest: String,
+    pub invalid_bid_count: i32,
+    pub coin_type: String,
+    // Funds of the highest bidder held by the auction
+    pub locked_value: String,
+    // Highest valid bid, which the next bid has to be above
+    pub highest_bid: Option<String>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = auctions_history)]
+pub struct AuctionHistory {
+    pub auction_id: String,
+    pub domain_name: String,
+    pub owner: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub min_bid: String,
+    pub winner: Option<String>,
+    pub amount: Option<String>,
+    pub status: AuctionStatus,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub last_tx_digest: String,
+    pub invalid_bid_count: i32,
+    pub coin_type: String,
+    pub locked_value: String,
+    pub highest_bid: Option<String>,
+    pub valid_from_checkpoint: i64,
+    pub valid_to_checkpoint: Option<i64>,
 }
 
 #[derive(Debug, Clone, AsChangeset, Serialize, Deserialize)]
@@ -147,16 +271,427 @@ pub struct Bid {
     pub amount: String,
     pub created_at: DateTime<Utc>,
     pub tx_digest: String,
+    pub is_valid: bool,
+    pub violations: Vec<String>,
+    pub coin_type: String,
+    // Empty for bids indexed before checkpoints were recorded
+    pub checkpoint: Option<i64>,
+    // Empty for bids indexed before event indexes were recorded
+    pub event_seq: Option<i64>,
 }
 
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
 )]
 #[diesel(sql_type = crate::schema::sql_types::Auctionstatus)]
+#[serde(rename_all = "kebab-case")]
 pub enum AuctionStatus {
     Created,
     Cancelled,
     Finalized,
+    // Derived from checkpoint time, not emitted by the contract
+    Active,
+    Ended,
+    EndedNoBids,
+}
+
+impl AuctionStatus {
+    /// Name of the variant in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuctionStatus::Created => "created",
+            AuctionStatus::Cancelled => "cancelled",
+            AuctionStatus::Finalized => "finalized",
+            AuctionStatus::Active => "active",
+            AuctionStatus::Ended => "ended",
+            AuctionStatus::EndedNoBids => "ended-no-bids",
+        }
+    }
+}
+
+impl std::str::FromStr for AuctionStatus {
+    type Err = anyhow::Error;
+
+    /// Parses the name of the variant in the database
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "created" => Ok(AuctionStatus::Created),
+            "cancelled" => Ok(AuctionStatus::Cancelled),
+            "finalized" => Ok(AuctionStatus::Finalized),
+            "active" => Ok(AuctionStatus::Active),
+            "ended" => Ok(AuctionStatus::Ended),
+            "ended-no-bids" => Ok(AuctionStatus::EndedNoBids),
+            _ => Err(anyhow::anyhow!("unknown auction status {:?}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = sales)]
+pub struct Sale {
+    pub domain_name: String,
+    pub seller: String,
+    pub buyer: String,
+    pub price: String,
+    pub sale_type: SaleType,
+    // Empty for sales backfilled from events indexed before checkpoints were recorded
+    pub checkpoint: Option<i64>,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+    pub coin_type: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = domains)]
+pub struct Domain {
+    pub domain_name: String,
+    pub open_offer_count: i32,
+    pub active_auction_id: Option<String>,
+    pub last_sale_price: Option<String>,
+    pub last_sale_at: Option<DateTime<Utc>>,
+    pub sale_count: i32,
+    pub updated_at: DateTime<Utc>,
+    pub label_length: Option<i32>,
+    pub char_class: Option<String>,
+    pub is_palindrome: Option<bool>,
+    pub has_repeating_pattern: Option<bool>,
+    pub is_subdomain: Option<bool>,
+    pub is_dictionary_word: Option<bool>,
+    pub last_sale_coin_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = domain_coin_stats)]
+pub struct DomainCoinStats {
+    pub domain_name: String,
+    pub coin_type: String,
+    pub open_offer_count: i32,
+    pub best_offer: Option<String>,
+    pub total_volume: String,
+    pub sale_count: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = domains)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateDomainCategory {
+    pub label_length: Option<i32>,
+    pub char_class: Option<String>,
+    pub is_palindrome: Option<bool>,
+    pub has_repeating_pattern: Option<bool>,
+    pub is_subdomain: Option<bool>,
+    pub is_dictionary_word: Option<bool>,
+}
+
+impl From<&crate::categorize::NameCategory> for UpdateDomainCategory {
+    fn from(category: &crate::categorize::NameCategory) -> Self {
+        Self {
+            label_length: Some(category.label_length as i32),
+            char_class: Some(category.char_class.as_str().to_string()),
+            is_palindrome: Some(category.is_palindrome),
+            has_repeating_pattern: Some(category.has_repeating_pattern),
+            is_subdomain: Some(category.is_subdomain),
+            is_dictionary_word: category.is_dictionary_word,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = crate::schema::sql_types::Saletype)]
+#[serde(rename_all = "kebab-case")]
+pub enum SaleType {
+    Offer,
+    CounterOffer,
+    Auction,
+}
+
+impl SaleType {
+    /// Name of the variant in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SaleType::Offer => "offer",
+            SaleType::CounterOffer => "counter-offer",
+            SaleType::Auction => "auction",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = account_activity)]
+pub struct AccountActivity {
+    pub address: String,
+    pub role: ActivityRole,
+    pub action: String,
+    pub domain_name: String,
+    pub counterparty: Option<String>,
+    pub amount: Option<String>,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+    pub coin_type: String,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = crate::schema::sql_types::Activityrole)]
+pub enum ActivityRole {
+    Buyer,
+    Owner,
+    Bidder,
+    Seller,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = accounts)]
+pub struct Account {
+    pub address: String,
+    pub total_spent: String,
+    pub total_received: String,
+    pub open_commitments: String,
+    pub purchase_count: i32,
+    pub sale_count: i32,
+    pub updated_at: DateTime<Utc>,
+    pub coin_type: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = daily_stats)]
+pub struct DailyStats {
+    pub day: NaiveDate,
+    pub offers_placed: i32,
+    pub offers_accepted: i32,
+    pub auctions_created: i32,
+    pub auctions_finalized: i32,
+    pub unique_buyers: i32,
+    pub unique_sellers: i32,
+    pub volume: String,
+    pub median_sale_price: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub coin_type: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = epoch_stats)]
+pub struct EpochStats {
+    pub epoch: i64,
+    pub offers_placed: i32,
+    pub offers_accepted: i32,
+    pub auctions_created: i32,
+    pub auctions_finalized: i32,
+    pub unique_buyers: i32,
+    pub unique_sellers: i32,
+    pub volume: String,
+    pub median_sale_price: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub coin_type: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = epochs)]
+pub struct Epoch {
+    pub epoch: i64,
+    pub first_checkpoint_at: DateTime<Utc>,
+    pub last_checkpoint_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = name_records)]
+pub struct NameRecord {
+    pub domain_name: String,
+    pub nft_id: String,
+    pub target_address: Option<String>,
+    pub expiration_timestamp_ms: i64,
+    pub expires_at: DateTime<Utc>,
+    pub registered_at: DateTime<Utc>,
+    pub last_renewed_at: Option<DateTime<Utc>>,
+    pub removed_at: Option<DateTime<Utc>>,
+    pub checkpoint: i64,
+    pub last_tx_digest: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = registration_owners)]
+pub struct RegistrationOwner {
+    pub nft_id: String,
+    pub domain_name: String,
+    pub owner_kind: OwnerKind,
+    // Only set for address and object owners
+    pub owner: Option<String>,
+    pub expiration_timestamp_ms: i64,
+    pub checkpoint: i64,
+    pub last_tx_digest: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // Last address that held the registration directly, kept while it's wrapped or object owned
+    pub holder: Option<String>,
+    pub transferred_at: Option<DateTime<Utc>>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = crate::schema::sql_types::Ownerkind)]
+pub enum OwnerKind {
+    Address,
+    Object,
+    Shared,
+    Immutable,
+    Wrapped,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, FieldCount, Serialize, Deserialize)]
+#[diesel(table_name = transactions)]
+pub struct Transaction {
+    pub tx_digest: String,
+    pub sender: String,
+    pub gas_budget: String,
+    pub gas_price: String,
+    pub computation_cost: String,
+    pub storage_cost: String,
+    pub storage_rebate: String,
+    pub non_refundable_storage_fee: String,
+    pub status: String,
+    pub error: Option<String>,
+    // Marketplace events emitted by the transaction
+    pub event_count: i32,
+    pub checkpoint: i64,
+    pub epoch: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = escrow_movements)]
+pub struct EscrowMovement {
+    pub escrow_kind: EscrowKind,
+    pub reference: String,
+    pub address: String,
+    pub domain_name: String,
+    pub direction: EscrowDirection,
+    pub reason: String,
+    pub amount: String,
+    pub coin_type: String,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = crate::schema::sql_types::Escrowkind)]
+pub enum EscrowKind {
+    Offer,
+    Auction,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, AsExpression, FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = crate::schema::sql_types::Escrowdirection)]
+pub enum EscrowDirection {
+    Lock,
+    Release,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = market_events)]
+pub struct MarketEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub domain_name: String,
+    pub addresses: Vec<String>,
+    pub data: serde_json::Value,
+    pub coin_type: String,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+    // Index of the event in its transaction, the same when a retried commit publishes it again
+    pub event_seq: i64,
+}
+
+impl MarketEvent {
+    /// The event as sent to feed clients and webhook endpoints
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "event_type": self.event_type,
+            "domain_name": self.domain_name,
+            "coin_type": self.coin_type,
+            "checkpoint": self.checkpoint,
+            "tx_digest": self.tx_digest,
+            "event_seq": self.event_seq,
+            "timestamp": self.created_at.to_rfc3339(),
+            "data": self.data,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = market_events)]
+pub struct NewMarketEvent {
+    pub event_type: String,
+    pub domain_name: String,
+    pub addresses: Vec<String>,
+    pub data: serde_json::Value,
+    pub coin_type: String,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+    // Index of the event in its transaction, the same when a retried commit publishes it again
+    pub event_seq: i64,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = webhook_subscriptions)]
+pub struct WebhookSubscription {
+    pub id: i32,
+    pub url: String,
+    // Empty to receive every event type
+    pub event_types: Vec<String>,
+    pub domain_name: Option<String>,
+    // Only events involving this address as buyer, owner, seller or winner
+    pub address: Option<String>,
+    pub secret: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = webhook_outbox)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i32,
+    pub event_type: String,
+    pub domain_name: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub failed_at: Option<DateTime<Utc>>,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = webhook_outbox)]
+pub struct NewWebhookDelivery {
+    pub subscription_id: i32,
+    pub event_type: String,
+    pub domain_name: String,
+    pub payload: serde_json::Value,
+    pub next_attempt_at: DateTime<Utc>,
+    pub checkpoint: i64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
 }
 
 impl diesel::serialize::ToSql<sql_types::Offerstatus, diesel::pg::Pg>
@@ -166,14 +701,7 @@ impl diesel::serialize::ToSql<sql_types::Offerstatus, diesel::pg::Pg>
         &'b self,
         out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
     ) -> diesel::serialize::Result {
-        let value = match self {
-            OfferStatus::Placed => "placed",
-            OfferStatus::Cancelled => "cancelled",
-            OfferStatus::Accepted => "accepted",
-            OfferStatus::Declined => "declined",
-            OfferStatus::Countered => "countered",
-            OfferStatus::AcceptedCountered => "accepted-countered",
-        };
+        let value = self.as_str();
         <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
             value,
             &mut out.reborrow(),
@@ -183,6 +711,68 @@ impl diesel::serialize::ToSql<sql_types::Offerstatus, diesel::pg::Pg>
 
 impl diesel::deserialize::FromSql<sql_types::Offerstatus, diesel::pg::Pg>
     for OfferStatus
+{
+    fn from_sql(
+        bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as diesel::deserialize::FromSql<
+            diesel::sql_types::Text,
+            diesel::pg::Pg,
+        >>::from_sql(bytes)?;
+        value
+            .parse()
+            .map_err(|_| "Unrecognized enum variant".into())
+    }
+}
+
+impl diesel::serialize::ToSql<sql_types::Auctionstatus, diesel::pg::Pg>
+for AuctionStatus
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        let value = self.as_str();
+        <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
+            value,
+            &mut out.reborrow(),
+        )
+    }
+}
+
+impl diesel::deserialize::FromSql<sql_types::Auctionstatus, diesel::pg::Pg>
+for AuctionStatus
+{
+    fn from_sql(
+        bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as diesel::deserialize::FromSql<
+            diesel::sql_types::Text,
+            diesel::pg::Pg,
+        >>::from_sql(bytes)?;
+        value
+            .parse()
+            .map_err(|_| "Unrecognized enum variant".into())
+    }
+}
+
+impl diesel::serialize::ToSql<sql_types::Saletype, diesel::pg::Pg>
+for SaleType
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        let value = self.as_str();
+        <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
+            value,
+            &mut out.reborrow(),
+        )
+    }
+}
+
+impl diesel::deserialize::FromSql<sql_types::Saletype, diesel::pg::Pg>
+for SaleType
 {
     fn from_sql(
         bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
@@ -192,28 +782,26 @@ impl diesel::deserialize::FromSql<sql_types::Offerstatus, diesel::pg::Pg>
             diesel::pg::Pg,
         >>::from_sql(bytes)?;
         match value.as_str() {
-            "placed" => Ok(OfferStatus::Placed),
-            "cancelled" => Ok(OfferStatus::Cancelled),
-            "accepted" => Ok(OfferStatus::Accepted),
-            "declined" => Ok(OfferStatus::Declined),
-            "countered" => Ok(OfferStatus::Countered),
-            "accepted-countered" => Ok(OfferStatus::AcceptedCountered),
+            "offer" => Ok(SaleType::Offer),
+            "counter-offer" => Ok(SaleType::CounterOffer),
+            "auction" => Ok(SaleType::Auction),
             _ => Err("Unrecognized enum variant".into()),
         }
     }
 }
 
-impl diesel::serialize::ToSql<sql_types::Auctionstatus, diesel::pg::Pg>
-for AuctionStatus
+impl diesel::serialize::ToSql<sql_types::Activityrole, diesel::pg::Pg>
+for ActivityRole
 {
     fn to_sql<'b>(
         &'b self,
         out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
     ) -> diesel::serialize::Result {
         let value = match self {
-            AuctionStatus::Created => "created",
-            AuctionStatus::Cancelled => "cancelled",
-            AuctionStatus::Finalized => "finalized",
+            ActivityRole::Buyer => "buyer",
+            ActivityRole::Owner => "owner",
+            ActivityRole::Bidder => "bidder",
+            ActivityRole::Seller => "seller",
         };
         <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
             value,
@@ -222,8 +810,8 @@ for AuctionStatus
     }
 }
 
-impl diesel::deserialize::FromSql<sql_types::Auctionstatus, diesel::pg::Pg>
-for AuctionStatus
+impl diesel::deserialize::FromSql<sql_types::Activityrole, diesel::pg::Pg>
+for ActivityRole
 {
     fn from_sql(
         bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
@@ -233,9 +821,126 @@ for AuctionStatus
             diesel::pg::Pg,
         >>::from_sql(bytes)?;
         match value.as_str() {
-            "created" => Ok(AuctionStatus::Created),
-            "cancelled" => Ok(AuctionStatus::Cancelled),
-            "finalized" => Ok(AuctionStatus::Finalized),
+            "buyer" => Ok(ActivityRole::Buyer),
+            "owner" => Ok(ActivityRole::Owner),
+            "bidder" => Ok(ActivityRole::Bidder),
+            "seller" => Ok(ActivityRole::Seller),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl diesel::serialize::ToSql<sql_types::Ownerkind, diesel::pg::Pg>
+for OwnerKind
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        let value = match self {
+            OwnerKind::Address => "address",
+            OwnerKind::Object => "object",
+            OwnerKind::Shared => "shared",
+            OwnerKind::Immutable => "immutable",
+            OwnerKind::Wrapped => "wrapped",
+            OwnerKind::Deleted => "deleted",
+        };
+        <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
+            value,
+            &mut out.reborrow(),
+        )
+    }
+}
+
+impl diesel::deserialize::FromSql<sql_types::Ownerkind, diesel::pg::Pg>
+for OwnerKind
+{
+    fn from_sql(
+        bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as diesel::deserialize::FromSql<
+            diesel::sql_types::Text,
+            diesel::pg::Pg,
+        >>::from_sql(bytes)?;
+        match value.as_str() {
+            "address" => Ok(OwnerKind::Address),
+            "object" => Ok(OwnerKind::Object),
+            "shared" => Ok(OwnerKind::Shared),
+            "immutable" => Ok(OwnerKind::Immutable),
+            "wrapped" => Ok(OwnerKind::Wrapped),
+            "deleted" => Ok(OwnerKind::Deleted),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl diesel::serialize::ToSql<sql_types::Escrowkind, diesel::pg::Pg>
+for EscrowKind
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        let value = match self {
+            EscrowKind::Offer => "offer",
+            EscrowKind::Auction => "auction",
+        };
+        <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
+            value,
+            &mut out.reborrow(),
+        )
+    }
+}
+
+impl diesel::deserialize::FromSql<sql_types::Escrowkind, diesel::pg::Pg>
+for EscrowKind
+{
+    fn from_sql(
+        bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as diesel::deserialize::FromSql<
+            diesel::sql_types::Text,
+            diesel::pg::Pg,
+        >>::from_sql(bytes)?;
+        match value.as_str() {
+            "offer" => Ok(EscrowKind::Offer),
+            "auction" => Ok(EscrowKind::Auction),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl diesel::serialize::ToSql<sql_types::Escrowdirection, diesel::pg::Pg>
+for EscrowDirection
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        let value = match self {
+            EscrowDirection::Lock => "lock",
+            EscrowDirection::Release => "release",
+        };
+        <str as diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg>>::to_sql(
+            value,
+            &mut out.reborrow(),
+        )
+    }
+}
+
+impl diesel::deserialize::FromSql<sql_types::Escrowdirection, diesel::pg::Pg>
+for EscrowDirection
+{
+    fn from_sql(
+        bytes: <diesel::pg::Pg as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        let value = <String as diesel::deserialize::FromSql<
+            diesel::sql_types::Text,
+            diesel::pg::Pg,
+        >>::from_sql(bytes)?;
+        match value.as_str() {
+            "lock" => Ok(EscrowDirection::Lock),
+            "release" => Ok(EscrowDirection::Release),
             _ => Err("Unrecognized enum variant".into()),
         }
     }
@@ -0,0 +1,181 @@
+use crate::models::{Account, AccountActivity, Sale};
+use crate::projections::{OPEN_AUCTION_STATUSES, OPEN_OFFER_STATUSES};
+use crate::schema::{account_activity, accounts, auctions, bids, offers};
+use anyhow::{Context, Error};
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Stores the activity of every address involved in a marketplace event
+pub async fn record_activity<'a>(
+    conn: &mut Connection<'a>,
+    activity: &[AccountActivity],
+) -> Result<()> {
+    if activity.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(account_activity::table)
+        .values(activity)
+        .execute(conn)
+        .await
+        .map_err(Into::<Error>::into)?;
+
+    Ok(())
+}
+
+// Make sure the account row exists and lock it, since both sequential pipelines update accounts
+async fn lock_account<'a>(
+    conn: &mut Connection<'a>,
+    address: &str,
+    coin_type: &str,
+    now: DateTime<Utc>,
+) -> Result<Account> {
+    diesel::insert_into(accounts::table)
+        .values(Account {
+            address: address.to_string(),
+            total_spent: "0".to_string(),
+            total_received: "0".to_string(),
+            open_commitments: "0".to_string(),
+            purchase_count: 0,
+            sale_count: 0,
+            updated_at: now,
+            coin_type: coin_type.to_string(),
+        })
+        .on_conflict((accounts::address, accounts::coin_type))
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    let account = accounts::table
+        .find((address, coin_type))
+        .select(Account::as_select())
+        .for_update()
+        .first(conn)
+        .await?;
+
+    Ok(account)
+}
+
+/// Adds a sale to the totals of the buyer and the seller
+pub async fn apply_sale<'a>(conn: &mut Connection<'a>, sale: &Sale) -> Result<()> {
+    let price = sale.price.parse::<u128>().context("invalid sale price")?;
+
+    // Lock both accounts in a stable order so concurrent sales can't deadlock
+    let mut addresses = vec![sale.buyer.as_str(), sale.seller.as_str()];
+    addresses.sort();
+    addresses.dedup();
+
+    for address in addresses {
+        let account = lock_account(conn, address, &sale.coin_type, sale.created_at).await?;
+
+        if address == sale.buyer {
+            let total_spent =
+                account.total_spent.parse::<u128>().context("invalid total spent")? + price;
+
+            diesel::update(accounts::table.find((address, &sale.coin_type)))
+                .set((
+                    accounts::total_spent.eq(total_spent.to_string()),
+                    accounts::purchase_count.eq(accounts::purchase_count + 1),
+                    accounts::updated_at.eq(sale.created_at),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        if address == sale.seller {
+            let total_received = account
+                .total_received
+                .parse::<u128>()
+                .context("invalid total received")?
+                + price;
+
+            diesel::update(accounts::table.find((address, &sale.coin_type)))
+                .set((
+                    accounts::total_received.eq(total_received.to_string()),
+                    accounts::sale_count.eq(accounts::sale_count + 1),
+                    accounts::updated_at.eq(sale.created_at),
+                ))
+                .execute(conn)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Recomputes the value an address has committed, in the given coin, to open offers and to
+/// auctions where it holds the highest bid
+pub async fn refresh_open_commitments<'a>(
+    conn: &mut Connection<'a>,
+    address: &str,
+    coin_type: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    lock_account(conn, address, coin_type, now).await?;
+
+    let mut open_commitments: u128 = 0;
+
+    let offer_values: Vec<String> = offers::table
+        .select(offers::value)
+        .filter(offers::buyer.eq(address))
+        .filter(offers::coin_type.eq(coin_type))
+        .filter(offers::status.eq_any(OPEN_OFFER_STATUSES))
+        .load(conn)
+        .await?;
+
+    for value in offer_values {
+        open_commitments += value.parse::<u128>().context("invalid offer value")?;
+    }
+
+    let auction_ids: Vec<String> = bids::table
+        .inner_join(auctions::table)
+        .select(bids::auction_id)
+        .filter(bids::bidder.eq(address))
+        .filter(bids::coin_type.eq(coin_type))
+        .filter(bids::is_valid.eq(true))
+        .filter(auctions::status.eq_any(OPEN_AUCTION_STATUSES))
+        .distinct()
+        .load(conn)
+        .await?;
+
+    for auction_id in auction_ids {
+        let auction_bids: Vec<(String, String)> = bids::table
+            .select((bids::bidder, bids::amount))
+            .filter(bids::auction_id.eq(&auction_id))
+            .filter(bids::is_valid.eq(true))
+            .load(conn)
+            .await?;
+
+        let mut highest_bid: Option<(u128, String)> = None;
+        for (bidder, amount) in auction_bids {
+            let amount = amount.parse::<u128>().context("invalid bid amount")?;
+            let is_highest = match &highest_bid {
+                Some((highest, _)) => amount > *highest,
+                None => true,
+            };
+            if is_highest {
+                highest_bid = Some((amount, bidder));
+            }
+        }
+
+        if let Some((amount, bidder)) = highest_bid {
+            if bidder == address {
+                open_commitments += amount;
+            }
+        }
+    }
+
+    diesel::update(accounts::table.find((address, coin_type)))
+        .set((
+            accounts::open_commitments.eq(open_commitments.to_string()),
+            accounts::updated_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
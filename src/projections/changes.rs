@@ -0,0 +1,89 @@
+use crate::projections::history;
+use diesel::sql_types::{BigInt, Integer, Nullable, Varchar};
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Channel notified with the new state of an offer every time it changes
+pub const OFFER_CHANNEL: &str = "offer_changes";
+
+/// Channel notified with the new state of an auction every time it changes
+pub const AUCTION_CHANNEL: &str = "auction_changes";
+
+// The payload is built from the row as written in the current transaction. Notifications are only
+// delivered once the transaction commits, and not at all if it rolls back.
+const NOTIFY_OFFER: &str = "
+    SELECT pg_notify($1, json_build_object(
+        'offer_id', id,
+        'domain_name', domain_name,
+        'buyer', buyer,
+        'owner', owner,
+        'status', status,
+        'amount', value,
+        'coin_type', coin_type,
+        'stale_reason', stale_reason,
+        'tx_digest', $3::VARCHAR,
+        'checkpoint', $4::BIGINT
+    )::TEXT)
+    FROM offers
+    WHERE id = $2
+";
+
+// The amount of an auction is its highest locked bid, and the winning amount once finalized
+const NOTIFY_AUCTION: &str = "
+    SELECT pg_notify($1, json_build_object(
+        'auction_id', auction_id,
+        'domain_name', domain_name,
+        'owner', owner,
+        'winner', winner,
+        'status', status,
+        'amount', COALESCE(amount, locked_value),
+        'coin_type', coin_type,
+        'tx_digest', $3::VARCHAR,
+        'checkpoint', $4::BIGINT
+    )::TEXT)
+    FROM auctions
+    WHERE auction_id = $2
+";
+
+/// Records a change of an offer: snapshots its state into the history and notifies listeners of
+/// `OFFER_CHANNEL`. `tx_digest` is the transaction that caused the change, if any, as changes
+/// derived from checkpoint time or other pipelines have none.
+pub async fn offer_changed<'a>(
+    conn: &mut Connection<'a>,
+    offer_id: i32,
+    tx_digest: Option<&str>,
+    checkpoint: u64,
+) -> Result<()> {
+    history::snapshot_offer(conn, offer_id, checkpoint).await?;
+
+    diesel::sql_query(NOTIFY_OFFER)
+        .bind::<Varchar, _>(OFFER_CHANNEL)
+        .bind::<Integer, _>(offer_id)
+        .bind::<Nullable<Varchar>, _>(tx_digest)
+        .bind::<BigInt, _>(checkpoint as i64)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Same as `offer_changed`, for auctions and `AUCTION_CHANNEL`
+pub async fn auction_changed<'a>(
+    conn: &mut Connection<'a>,
+    auction_id: &str,
+    tx_digest: Option<&str>,
+    checkpoint: u64,
+) -> Result<()> {
+    history::snapshot_auction(conn, auction_id, checkpoint).await?;
+
+    diesel::sql_query(NOTIFY_AUCTION)
+        .bind::<Varchar, _>(AUCTION_CHANNEL)
+        .bind::<Varchar, _>(auction_id)
+        .bind::<Nullable<Varchar>, _>(tx_digest)
+        .bind::<BigInt, _>(checkpoint as i64)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use crate::categorize::categorize;
+use crate::models::{Domain, DomainCoinStats, Sale, UpdateDomainCategory};
+use crate::projections::OPEN_OFFER_STATUSES;
+use crate::schema::{domain_coin_stats, domains, offers};
+use anyhow::Context;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::info;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+// Make sure the domain row exists so the updates below always have something to update
+async fn ensure_domain<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let category = UpdateDomainCategory::from(&categorize(domain_name));
+
+    diesel::insert_into(domains::table)
+        .values(Domain {
+            domain_name: domain_name.to_string(),
+            open_offer_count: 0,
+            active_auction_id: None,
+            last_sale_price: None,
+            last_sale_at: None,
+            sale_count: 0,
+            updated_at: now,
+            label_length: category.label_length,
+            char_class: category.char_class,
+            is_palindrome: category.is_palindrome,
+            has_repeating_pattern: category.has_repeating_pattern,
+            is_subdomain: category.is_subdomain,
+            is_dictionary_word: category.is_dictionary_word,
+            last_sale_coin_type: None,
+        })
+        .on_conflict(domains::domain_name)
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+// Same as `ensure_domain`, for the per coin aggregates of a domain
+async fn ensure_domain_coin<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    coin_type: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    ensure_domain(conn, domain_name, now).await?;
+
+    diesel::insert_into(domain_coin_stats::table)
+        .values(DomainCoinStats {
+            domain_name: domain_name.to_string(),
+            coin_type: coin_type.to_string(),
+            open_offer_count: 0,
+            best_offer: None,
+            total_volume: "0".to_string(),
+            sale_count: 0,
+            updated_at: now,
+        })
+        .on_conflict((domain_coin_stats::domain_name, domain_coin_stats::coin_type))
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Recomputes the open offer counts of a domain, and its best open offer in the given coin, from
+/// the `offers` table. Offers are ranked by what their buyer proposed, not by the owner's counter.
+pub async fn refresh_offers<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    coin_type: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    ensure_domain_coin(conn, domain_name, coin_type, now).await?;
+
+    let values: Vec<String> = offers::table
+        .select(offers::buyer_value)
+        .filter(offers::domain_name.eq(domain_name))
+        .filter(offers::coin_type.eq(coin_type))
+        .filter(offers::status.eq_any(OPEN_OFFER_STATUSES))
+        .load(conn)
+        .await?;
+
+    let mut best_offer: Option<u64> = None;
+    for value in &values {
+        let value = value.parse::<u64>().context("invalid offer value")?;
+        best_offer = best_offer.max(Some(value));
+    }
+
+    diesel::update(domain_coin_stats::table.find((domain_name, coin_type)))
+        .set((
+            domain_coin_stats::open_offer_count.eq(values.len() as i32),
+            domain_coin_stats::best_offer.eq(best_offer.map(|value| value.to_string())),
+            domain_coin_stats::updated_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+
+    let open_offer_count: i64 = offers::table
+        .filter(offers::domain_name.eq(domain_name))
+        .filter(offers::status.eq_any(OPEN_OFFER_STATUSES))
+        .count()
+        .get_result(conn)
+        .await?;
+
+    diesel::update(domains::table.find(domain_name))
+        .set((
+            domains::open_offer_count.eq(open_offer_count as i32),
+            domains::updated_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Marks an auction as the one currently running for a domain
+pub async fn set_active_auction<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    auction_id: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    ensure_domain(conn, domain_name, now).await?;
+
+    diesel::update(domains::table.find(domain_name))
+        .set((
+            domains::active_auction_id.eq(Some(auction_id)),
+            domains::updated_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Clears the active auction of a domain, unless another auction has replaced it in the meantime
+pub async fn clear_active_auction<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    auction_id: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    diesel::update(
+        domains::table
+            .filter(domains::domain_name.eq(domain_name))
+            .filter(domains::active_auction_id.eq(auction_id)),
+    )
+    .set((
+        domains::active_auction_id.eq(None::<String>),
+        domains::updated_at.eq(now),
+    ))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Updates the last sale of a domain and its total volume in the coin of the sale
+pub async fn apply_sale<'a>(conn: &mut Connection<'a>, sale: &Sale) -> Result<()> {
+    ensure_domain_coin(conn, &sale.domain_name, &sale.coin_type, sale.created_at).await?;
+
+    // Both sequential pipelines record sales, so lock the row while adding to the volume
+    let total_volume: String = domain_coin_stats::table
+        .select(domain_coin_stats::total_volume)
+        .filter(domain_coin_stats::domain_name.eq(&sale.domain_name))
+        .filter(domain_coin_stats::coin_type.eq(&sale.coin_type))
+        .for_update()
+        .first(conn)
+        .await?;
+
+    let total_volume = total_volume
+        .parse::<u128>()
+        .context("invalid domain total volume")?
+        + sale.price.parse::<u128>().context("invalid sale price")?;
+
+    diesel::update(domain_coin_stats::table.find((&sale.domain_name, &sale.coin_type)))
+        .set((
+            domain_coin_stats::total_volume.eq(total_volume.to_string()),
+            domain_coin_stats::sale_count.eq(domain_coin_stats::sale_count + 1),
+            domain_coin_stats::updated_at.eq(sale.created_at),
+        ))
+        .execute(conn)
+        .await?;
+
+    diesel::update(domains::table.find(&sale.domain_name))
+        .set((
+            domains::sale_count.eq(domains::sale_count + 1),
+            domains::updated_at.eq(sale.created_at),
+        ))
+        .execute(conn)
+        .await?;
+
+    // The offers and auctions pipelines commit independently, so the one behind must not replace a
+    // later sale with an earlier one
+    diesel::update(
+        domains::table.find(&sale.domain_name).filter(
+            domains::last_sale_at
+                .is_null()
+                .or(domains::last_sale_at.le(sale.created_at)),
+        ),
+    )
+    .set((
+        domains::last_sale_price.eq(Some(&sale.price)),
+        domains::last_sale_coin_type.eq(Some(&sale.coin_type)),
+        domains::last_sale_at.eq(Some(sale.created_at)),
+    ))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Recomputes the category tags of every domain, e.g. after the wordlist changed
+pub async fn categorize_all<'a>(conn: &mut Connection<'a>) -> Result<()> {
+    let domain_names: Vec<String> = domains::table
+        .select(domains::domain_name)
+        .load(conn)
+        .await?;
+
+    info!("Categorizing {} domains", domain_names.len());
+
+    for domain_name in domain_names {
+        diesel::update(domains::table.find(&domain_name))
+            .set(UpdateDomainCategory::from(&categorize(&domain_name)))
+            .execute(conn)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use crate::models::{EscrowDirection, EscrowKind, EscrowMovement};
+use crate::schema::{auctions, escrow_movements, offers};
+use anyhow::Context;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Text, Varchar};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::{info, warn};
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+// Net amount locked per address in the escrow of a single offer or auction
+const LOCKED_BY_ADDRESS: &str = "
+    SELECT address, SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END)::VARCHAR AS locked
+    FROM escrow_movements
+    WHERE escrow_kind = $1::EscrowKind AND reference = $2
+    GROUP BY address
+";
+
+// Escrow of every buyer's offers on a domain and of every auction, as implied by the raw events,
+// next to the locked values tracked on offers and auctions and the net escrow movements. An offer
+// locks its placed value until it's cancelled, declined or accepted, and offers close in the order
+// they were placed, so the ones still open are the latest placed. An auction locks its highest
+// valid bid until it's cancelled or finalized.
+const ESCROW_BY_REFERENCE: &str = "
+    WITH placed AS (
+        SELECT domain_name, address, coin_type, value,
+            ROW_NUMBER() OVER (PARTITION BY domain_name, address, coin_type ORDER BY created_at DESC, id DESC) AS recency,
+            COUNT(*) OVER (PARTITION BY domain_name, address, coin_type) AS placed_count
+        FROM offer_placed
+    ), closed AS (
+        SELECT domain_name, address, coin_type, COUNT(*) AS closed_count
+        FROM (
+            SELECT domain_name, address, coin_type FROM offer_cancelled
+            UNION ALL SELECT domain_name, address, coin_type FROM offer_declined
+            UNION ALL SELECT domain_name, address, coin_type FROM offer_accepted
+            UNION ALL SELECT domain_name, address, coin_type FROM accept_counter_offer
+        ) t
+        GROUP BY domain_name, address, coin_type
+    ), offers_implied AS (
+        SELECT placed.domain_name, placed.address, placed.coin_type, SUM(placed.value::NUMERIC) AS locked
+        FROM placed
+        LEFT JOIN closed USING (domain_name, address, coin_type)
+        WHERE placed.recency <= placed.placed_count - COALESCE(closed.closed_count, 0)
+        GROUP BY placed.domain_name, placed.address, placed.coin_type
+    ), offers_tracked AS (
+        SELECT domain_name, buyer AS address, coin_type, SUM(locked_value::NUMERIC) AS locked
+        FROM offers
+        GROUP BY domain_name, buyer, coin_type
+    ), offers_ledger AS (
+        SELECT domain_name, address, coin_type, SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END) AS locked
+        FROM escrow_movements
+        WHERE escrow_kind = 'offer'
+        GROUP BY domain_name, address, coin_type
+    ), offer_keys AS (
+        SELECT domain_name, address, coin_type FROM offers_implied
+        UNION SELECT domain_name, address, coin_type FROM offers_tracked
+        UNION SELECT domain_name, address, coin_type FROM offers_ledger
+    ), auctions_ledger AS (
+        SELECT reference, SUM(CASE WHEN direction = 'lock' THEN amount::NUMERIC ELSE -amount::NUMERIC END) AS locked
+        FROM escrow_movements
+        WHERE escrow_kind = 'auction'
+        GROUP BY reference
+    ), escrow AS (
+        SELECT
+            'offer' AS escrow_kind,
+            offer_keys.coin_type,
+            offer_keys.domain_name || ' from ' || offer_keys.address AS reference,
+            COALESCE(offers_implied.locked, 0) AS implied,
+            COALESCE(offers_tracked.locked, 0) AS tracked,
+            COALESCE(offers_ledger.locked, 0) AS ledger
+        FROM offer_keys
+        LEFT JOIN offers_implied USING (domain_name, address, coin_type)
+        LEFT JOIN offers_tracked USING (domain_name, address, coin_type)
+        LEFT JOIN offers_ledger USING (domain_name, address, coin_type)
+        UNION ALL
+        SELECT
+            'auction',
+            auctions.coin_type,
+            auctions.auction_id,
+            CASE WHEN auctions.status IN ('cancelled', 'finalized') THEN 0
+                 ELSE COALESCE((SELECT MAX(amount::NUMERIC) FROM bids WHERE bids.auction_id = auctions.auction_id AND bids.is_valid), 0) END,
+            auctions.locked_value::NUMERIC,
+            COALESCE(auctions_ledger.locked, 0)
+        FROM auctions
+        LEFT JOIN auctions_ledger ON auctions_ledger.reference = auctions.auction_id
+    )
+";
+
+// Offers and auctions whose tracked locked value or escrow movements disagree with their events
+const RECONCILE_REFERENCES: &str = "
+    SELECT escrow_kind, reference, implied::VARCHAR AS implied, tracked::VARCHAR AS tracked, ledger::VARCHAR AS ledger
+    FROM escrow
+    WHERE implied <> tracked OR implied <> ledger
+    ORDER BY escrow_kind, reference
+";
+
+// Total escrow per coin, as implied by the events and as tracked on offers and auctions
+const RECONCILE_COIN_TOTALS: &str = "
+    SELECT coin_type, SUM(implied)::VARCHAR AS implied, SUM(tracked)::VARCHAR AS tracked
+    FROM escrow
+    GROUP BY coin_type
+    ORDER BY coin_type
+";
+
+#[derive(QueryableByName)]
+struct LockedByAddress {
+    #[diesel(sql_type = Varchar)]
+    address: String,
+    #[diesel(sql_type = Text)]
+    locked: String,
+}
+
+#[derive(QueryableByName)]
+struct ReferenceMismatch {
+    #[diesel(sql_type = Text)]
+    escrow_kind: String,
+    #[diesel(sql_type = Text)]
+    reference: String,
+    #[diesel(sql_type = Text)]
+    implied: String,
+    #[diesel(sql_type = Text)]
+    tracked: String,
+    #[diesel(sql_type = Text)]
+    ledger: String,
+}
+
+#[derive(QueryableByName)]
+struct CoinTotals {
+    #[diesel(sql_type = Varchar)]
+    coin_type: String,
+    #[diesel(sql_type = Text)]
+    implied: String,
+    #[diesel(sql_type = Text)]
+    tracked: String,
+}
+
+/// Where and when escrowed funds moved
+#[derive(Debug, Clone)]
+pub struct EscrowContext {
+    pub domain_name: String,
+    pub coin_type: String,
+    pub checkpoint: u64,
+    pub tx_digest: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl EscrowContext {
+    fn movement(
+        &self,
+        escrow_kind: EscrowKind,
+        reference: &str,
+        address: &str,
+        direction: EscrowDirection,
+        reason: &str,
+        amount: String,
+    ) -> EscrowMovement {
+        EscrowMovement {
+            escrow_kind,
+            reference: reference.to_string(),
+            address: address.to_string(),
+            domain_name: self.domain_name.clone(),
+            direction,
+            reason: reason.to_string(),
+            amount,
+            coin_type: self.coin_type.clone(),
+            checkpoint: self.checkpoint as i64,
+            tx_digest: self.tx_digest.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Records funds of an address locked in an offer or auction
+pub async fn lock<'a>(
+    conn: &mut Connection<'a>,
+    escrow_kind: EscrowKind,
+    reference: &str,
+    address: &str,
+    amount: u64,
+    reason: &str,
+    context: &EscrowContext,
+) -> Result<()> {
+    diesel::insert_into(escrow_movements::table)
+        .values(context.movement(
+            escrow_kind,
+            reference,
+            address,
+            EscrowDirection::Lock,
+            reason,
+            amount.to_string(),
+        ))
+        .execute(conn)
+        .await?;
+
+    update_locked_value(conn, escrow_kind, reference).await
+}
+
+/// Releases everything still locked in an offer or auction, e.g. on cancel, outbid or settlement
+pub async fn release_all<'a>(
+    conn: &mut Connection<'a>,
+    escrow_kind: EscrowKind,
+    reference: &str,
+    reason: &str,
+    context: &EscrowContext,
+) -> Result<()> {
+    let locked: Vec<LockedByAddress> = diesel::sql_query(LOCKED_BY_ADDRESS)
+        .bind::<Text, _>(escrow_kind_str(escrow_kind))
+        .bind::<Varchar, _>(reference)
+        .load(conn)
+        .await?;
+
+    let mut movements = Vec::new();
+    for LockedByAddress { address, locked } in locked {
+        let locked = locked.parse::<u128>().context("invalid locked amount")?;
+        if locked > 0 {
+            movements.push(context.movement(
+                escrow_kind,
+                reference,
+                &address,
+                EscrowDirection::Release,
+                reason,
+                locked.to_string(),
+            ));
+        }
+    }
+
+    if movements.is_empty() {
+        return Ok(());
+    }
+
+    diesel::insert_into(escrow_movements::table)
+        .values(movements)
+        .execute(conn)
+        .await?;
+
+    update_locked_value(conn, escrow_kind, reference).await
+}
+
+/// Derives the escrow of offers and auctions from their raw events and compares it with the locked
+/// values tracked on them and with the escrow movements, logging every mismatch. Returns the number
+/// of mismatches found.
+///
+/// The raw offer events are written by a separate pipeline, so this is only meaningful while the
+/// pipelines are caught up with each other, e.g. with the indexer stopped.
+pub async fn reconcile<'a>(conn: &mut Connection<'a>) -> Result<usize> {
+    let mut mismatches = 0;
+
+    let totals: Vec<CoinTotals> =
+        diesel::sql_query(format!("{ESCROW_BY_REFERENCE} {RECONCILE_COIN_TOTALS}"))
+            .load(conn)
+            .await?;
+
+    for CoinTotals {
+        coin_type,
+        implied,
+        tracked,
+    } in totals
+    {
+        if implied == tracked {
+            info!(
+                "Escrow in {} holds {}, matching the events",
+                coin_type, tracked
+            );
+        } else {
+            warn!(
+                "Escrow in {} holds {}, but the escrow implied by events is {}",
+                coin_type, tracked, implied
+            );
+            mismatches += 1;
+        }
+    }
+
+    let references: Vec<ReferenceMismatch> =
+        diesel::sql_query(format!("{ESCROW_BY_REFERENCE} {RECONCILE_REFERENCES}"))
+            .load(conn)
+            .await?;
+
+    for ReferenceMismatch {
+        escrow_kind,
+        reference,
+        implied,
+        tracked,
+        ledger,
+    } in references
+    {
+        warn!(
+            "Escrow of {} {} is tracked as {} with {} in escrow movements, but the escrow implied \
+             by events is {}",
+            escrow_kind, reference, tracked, ledger, implied
+        );
+        mismatches += 1;
+    }
+
+    Ok(mismatches)
+}
+
+fn escrow_kind_str(escrow_kind: EscrowKind) -> &'static str {
+    match escrow_kind {
+        EscrowKind::Offer => "offer",
+        EscrowKind::Auction => "auction",
+    }
+}
+
+// Keeps the locked value of the offer or auction in line with its escrow movements
+async fn update_locked_value<'a>(
+    conn: &mut Connection<'a>,
+    escrow_kind: EscrowKind,
+    reference: &str,
+) -> Result<()> {
+    let locked: Vec<LockedByAddress> = diesel::sql_query(LOCKED_BY_ADDRESS)
+        .bind::<Text, _>(escrow_kind_str(escrow_kind))
+        .bind::<Varchar, _>(reference)
+        .load(conn)
+        .await?;
+
+    let mut locked_value: u128 = 0;
+    for LockedByAddress { locked, .. } in locked {
+        locked_value += locked.parse::<u128>().context("invalid locked amount")?;
+    }
+
+    match escrow_kind {
+        EscrowKind::Offer => {
+            let id = reference.parse::<i32>().context("invalid offer id")?;
+            diesel::update(offers::table.filter(offers::id.eq(id)))
+                .set(offers::locked_value.eq(locked_value.to_string()))
+                .execute(conn)
+                .await?;
+        }
+        EscrowKind::Auction => {
+            diesel::update(auctions::table.filter(auctions::auction_id.eq(reference)))
+                .set(auctions::locked_value.eq(locked_value.to_string()))
+                .execute(conn)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
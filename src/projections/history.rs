@@ -0,0 +1,213 @@
+use crate::models::{AuctionHistory, OfferHistory};
+use crate::projections::{OPEN_AUCTION_STATUSES, OPEN_OFFER_STATUSES};
+use crate::schema::{auctions_history, offers_history};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Varchar};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+const OFFER_COLUMNS: &str = "domain_name, buyer, initial_value, value, owner, status, updated_at, \
+    created_at, last_tx_digest, coin_type, stale_reason, locked_value, buyer_value, counter_value";
+
+const AUCTION_COLUMNS: &str = "domain_name, owner, start_time, end_time, min_bid, winner, amount, \
+    status, updated_at, created_at, last_tx_digest, invalid_bid_count, coin_type, locked_value, \
+    highest_bid";
+
+/// Records the current state of an offer as valid from the given checkpoint, or from the checkpoint
+/// of its latest state when that is later, closing the state it replaces. Called through
+/// `changes::offer_changed` after every change to the offer.
+pub async fn snapshot_offer<'a>(
+    conn: &mut Connection<'a>,
+    offer_id: i32,
+    checkpoint: u64,
+) -> Result<()> {
+    // Other pipelines change offers too, e.g. marking them stale, and can be behind the pipeline
+    // that recorded the latest state. Their changes are recorded at that state's checkpoint, so no
+    // state ends before it begins.
+    let latest: Option<i64> = offers_history::table
+        .filter(offers_history::offer_id.eq(offer_id))
+        .select(diesel::dsl::max(offers_history::valid_from_checkpoint))
+        .first(conn)
+        .await?;
+    let checkpoint = latest.map_or(checkpoint as i64, |latest| latest.max(checkpoint as i64));
+
+    // A state replaced within the checkpoint it started in was never visible at a checkpoint
+    diesel::delete(
+        offers_history::table
+            .filter(offers_history::offer_id.eq(offer_id))
+            .filter(offers_history::valid_from_checkpoint.eq(checkpoint)),
+    )
+    .execute(conn)
+    .await?;
+
+    diesel::update(
+        offers_history::table
+            .filter(offers_history::offer_id.eq(offer_id))
+            .filter(offers_history::valid_to_checkpoint.is_null()),
+    )
+    .set(offers_history::valid_to_checkpoint.eq(Some(checkpoint)))
+    .execute(conn)
+    .await?;
+
+    diesel::sql_query(format!(
+        "INSERT INTO offers_history (offer_id, {OFFER_COLUMNS}, valid_from_checkpoint) \
+         SELECT id, {OFFER_COLUMNS}, $2 FROM offers WHERE id = $1"
+    ))
+    .bind::<Integer, _>(offer_id)
+    .bind::<BigInt, _>(checkpoint)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Same as `snapshot_offer`, for auctions
+pub async fn snapshot_auction<'a>(
+    conn: &mut Connection<'a>,
+    auction_id: &str,
+    checkpoint: u64,
+) -> Result<()> {
+    let latest: Option<i64> = auctions_history::table
+        .filter(auctions_history::auction_id.eq(auction_id))
+        .select(diesel::dsl::max(auctions_history::valid_from_checkpoint))
+        .first(conn)
+        .await?;
+    let checkpoint = latest.map_or(checkpoint as i64, |latest| latest.max(checkpoint as i64));
+
+    diesel::delete(
+        auctions_history::table
+            .filter(auctions_history::auction_id.eq(auction_id))
+            .filter(auctions_history::valid_from_checkpoint.eq(checkpoint)),
+    )
+    .execute(conn)
+    .await?;
+
+    diesel::update(
+        auctions_history::table
+            .filter(auctions_history::auction_id.eq(auction_id))
+            .filter(auctions_history::valid_to_checkpoint.is_null()),
+    )
+    .set(auctions_history::valid_to_checkpoint.eq(Some(checkpoint)))
+    .execute(conn)
+    .await?;
+
+    diesel::sql_query(format!(
+        "INSERT INTO auctions_history (auction_id, {AUCTION_COLUMNS}, valid_from_checkpoint) \
+         SELECT auction_id, {AUCTION_COLUMNS}, $2 FROM auctions WHERE auction_id = $1"
+    ))
+    .bind::<Varchar, _>(auction_id)
+    .bind::<BigInt, _>(checkpoint)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// State of an offer at the end of the given checkpoint, if it existed by then
+pub async fn offer_at<'a>(
+    conn: &mut Connection<'a>,
+    offer_id: i32,
+    checkpoint: u64,
+) -> Result<Option<OfferHistory>> {
+    let checkpoint = checkpoint as i64;
+
+    let offer = offers_history::table
+        .filter(offers_history::offer_id.eq(offer_id))
+        .filter(offers_history::valid_from_checkpoint.le(checkpoint))
+        .filter(
+            offers_history::valid_to_checkpoint
+                .is_null()
+                .or(offers_history::valid_to_checkpoint.gt(checkpoint)),
+        )
+        .select(OfferHistory::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(offer)
+}
+
+/// State of an auction at the end of the given checkpoint, if it existed by then
+pub async fn auction_at<'a>(
+    conn: &mut Connection<'a>,
+    auction_id: &str,
+    checkpoint: u64,
+) -> Result<Option<AuctionHistory>> {
+    let checkpoint = checkpoint as i64;
+
+    let auction = auctions_history::table
+        .filter(auctions_history::auction_id.eq(auction_id))
+        .filter(auctions_history::valid_from_checkpoint.le(checkpoint))
+        .filter(
+            auctions_history::valid_to_checkpoint
+                .is_null()
+                .or(auctions_history::valid_to_checkpoint.gt(checkpoint)),
+        )
+        .select(AuctionHistory::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(auction)
+}
+
+/// Offers that were open at the end of the given checkpoint, optionally for a single domain
+pub async fn open_offers_at<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: Option<&str>,
+    checkpoint: u64,
+) -> Result<Vec<OfferHistory>> {
+    let checkpoint = checkpoint as i64;
+
+    let mut query = offers_history::table
+        .filter(offers_history::valid_from_checkpoint.le(checkpoint))
+        .filter(
+            offers_history::valid_to_checkpoint
+                .is_null()
+                .or(offers_history::valid_to_checkpoint.gt(checkpoint)),
+        )
+        .filter(offers_history::status.eq_any(OPEN_OFFER_STATUSES))
+        .select(OfferHistory::as_select())
+        .into_boxed();
+
+    if let Some(domain_name) = domain_name {
+        query = query.filter(offers_history::domain_name.eq(domain_name));
+    }
+
+    let offers = query.order(offers_history::offer_id.asc()).load(conn).await?;
+
+    Ok(offers)
+}
+
+/// Auctions that were open at the end of the given checkpoint, optionally for a single domain
+pub async fn open_auctions_at<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: Option<&str>,
+    checkpoint: u64,
+) -> Result<Vec<AuctionHistory>> {
+    let checkpoint = checkpoint as i64;
+
+    let mut query = auctions_history::table
+        .filter(auctions_history::valid_from_checkpoint.le(checkpoint))
+        .filter(
+            auctions_history::valid_to_checkpoint
+                .is_null()
+                .or(auctions_history::valid_to_checkpoint.gt(checkpoint)),
+        )
+        .filter(auctions_history::status.eq_any(OPEN_AUCTION_STATUSES))
+        .select(AuctionHistory::as_select())
+        .into_boxed();
+
+    if let Some(domain_name) = domain_name {
+        query = query.filter(auctions_history::domain_name.eq(domain_name));
+    }
+
+    let auctions = query
+        .order(auctions_history::auction_id.asc())
+        .load(conn)
+        .await?;
+
+    Ok(auctions)
+}
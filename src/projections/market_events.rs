@@ -0,0 +1,21 @@
+use crate::models::{MarketEvent, NewMarketEvent};
+use crate::schema::market_events;
+use crate::webhooks;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Stores a decoded marketplace event for the live feed and the event sinks, and queues it for the
+/// webhooks it matches. Called by the pipelines in their commit transaction.
+pub async fn publish<'a>(conn: &mut Connection<'a>, event: &NewMarketEvent) -> Result<MarketEvent> {
+    let event = diesel::insert_into(market_events::table)
+        .values(event)
+        .returning(MarketEvent::as_returning())
+        .get_result(conn)
+        .await?;
+
+    webhooks::enqueue(conn, &event).await?;
+
+    Ok(event)
+}
@@ -0,0 +1,21 @@
+use crate::models::{AuctionStatus, OfferStatus};
+
+pub mod accounts;
+pub mod changes;
+pub mod domains;
+pub mod escrow;
+pub mod history;
+pub mod market_events;
+pub mod sales;
+pub mod stale_offers;
+pub mod stats;
+
+// Offers that can still be acted upon by the owner or the buyer
+pub const OPEN_OFFER_STATUSES: [OfferStatus; 2] = [OfferStatus::Placed, OfferStatus::Countered];
+
+// Auctions whose bids are still locked, either running or waiting to be finalized
+pub const OPEN_AUCTION_STATUSES: [AuctionStatus; 3] = [
+    AuctionStatus::Created,
+    AuctionStatus::Active,
+    AuctionStatus::Ended,
+];
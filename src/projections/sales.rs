@@ -0,0 +1,27 @@
+use crate::models::Sale;
+use crate::projections::{accounts, domains};
+use crate::schema::sales;
+use anyhow::Error;
+use diesel_async::RunQueryDsl;
+use log::info;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Stores a sale and updates the aggregates of the sold domain and of both parties
+pub async fn record_sale<'a>(conn: &mut Connection<'a>, sale: Sale) -> Result<()> {
+    info!(
+        "Recording {:?} sale of domain {} from {} to {} for {}",
+        sale.sale_type, sale.domain_name, sale.seller, sale.buyer, sale.price
+    );
+
+    diesel::insert_into(sales::table)
+        .values(&sale)
+        .execute(conn)
+        .await
+        .map_err(Into::<Error>::into)?;
+
+    domains::apply_sale(conn, &sale).await?;
+    accounts::apply_sale(conn, &sale).await?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use crate::models::{OfferStatus, SaleType};
+use crate::projections::{accounts, changes, domains, OPEN_OFFER_STATUSES};
+use crate::schema::{name_records, offers, registration_owners, sales};
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::info;
+use std::collections::BTreeSet;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Why an open offer can no longer be accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleReason {
+    SoldInAuction,
+    SoldToOtherOffer,
+    Transferred,
+    Expired,
+}
+
+impl StaleReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StaleReason::SoldInAuction => "sold-in-auction",
+            StaleReason::SoldToOtherOffer => "sold-to-other-offer",
+            StaleReason::Transferred => "transferred",
+            StaleReason::Expired => "expired",
+        }
+    }
+}
+
+/// Marks the open offers of a domain as stale when the domain was sold or transferred after they
+/// were last acted upon, or when its name expired, and refreshes the aggregates they counted in.
+///
+/// The pipelines writing sales, ownership and name records run independently, so every one of
+/// them calls this for the domains it touched and whichever runs last catches the stale offers.
+pub async fn invalidate_stale<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    now: DateTime<Utc>,
+    checkpoint: u64,
+) -> Result<()> {
+    let mut stale = BTreeSet::new();
+
+    let last_sale: Option<(DateTime<Utc>, SaleType)> = sales::table
+        .select((sales::created_at, sales::sale_type))
+        .filter(sales::domain_name.eq(domain_name))
+        .order(sales::created_at.desc())
+        .first(conn)
+        .await
+        .optional()?;
+
+    if let Some((sold_at, sale_type)) = last_sale {
+        let reason = match sale_type {
+            SaleType::Auction => StaleReason::SoldInAuction,
+            SaleType::Offer | SaleType::CounterOffer => StaleReason::SoldToOtherOffer,
+        };
+        stale.extend(mark_stale(conn, domain_name, sold_at, reason, checkpoint).await?);
+    }
+
+    let transferred_at: Option<DateTime<Utc>> = registration_owners::table
+        .select(diesel::dsl::max(registration_owners::transferred_at))
+        .filter(registration_owners::domain_name.eq(domain_name))
+        .first(conn)
+        .await?;
+
+    if let Some(transferred_at) = transferred_at {
+        let reason = StaleReason::Transferred;
+        stale.extend(mark_stale(conn, domain_name, transferred_at, reason, checkpoint).await?);
+    }
+
+    let name_record: Option<(DateTime<Utc>, Option<DateTime<Utc>>)> = name_records::table
+        .select((name_records::expires_at, name_records::removed_at))
+        .filter(name_records::domain_name.eq(domain_name))
+        .first(conn)
+        .await
+        .optional()?;
+
+    let expired_at = match name_record {
+        Some((expires_at, _)) if expires_at < now => Some(expires_at),
+        Some((_, removed_at)) => removed_at,
+        None => None,
+    };
+
+    if let Some(expired_at) = expired_at {
+        let reason = StaleReason::Expired;
+        stale.extend(mark_stale(conn, domain_name, expired_at, reason, checkpoint).await?);
+    }
+
+    let coin_types: BTreeSet<&String> = stale.iter().map(|(_, coin_type)| coin_type).collect();
+    for coin_type in coin_types {
+        domains::refresh_offers(conn, domain_name, coin_type, now).await?;
+    }
+
+    for (buyer, coin_type) in &stale {
+        accounts::refresh_open_commitments(conn, buyer, coin_type, now).await?;
+    }
+
+    Ok(())
+}
+
+/// Runs `invalidate_stale` for every domain with open offers whose name expired or was removed
+pub async fn invalidate_expired<'a>(
+    conn: &mut Connection<'a>,
+    now: DateTime<Utc>,
+    checkpoint: u64,
+) -> Result<()> {
+    let domain_names: Vec<String> = offers::table
+        .inner_join(name_records::table.on(name_records::domain_name.eq(offers::domain_name)))
+        .filter(offers::status.eq_any(OPEN_OFFER_STATUSES))
+        // Offers made after the name expired are left alone, so they don't come up every time
+        .filter(
+            name_records::expires_at
+                .lt(now)
+                .and(offers::updated_at.lt(name_records::expires_at))
+                .or(offers::updated_at.nullable().lt(name_records::removed_at)),
+        )
+        .select(offers::domain_name)
+        .distinct()
+        .load(conn)
+        .await?;
+
+    for domain_name in domain_names {
+        invalidate_stale(conn, &domain_name, now, checkpoint).await?;
+    }
+
+    Ok(())
+}
+
+// Offers acted upon after `stale_at` are left alone, e.g. a counter offer made by the new owner
+async fn mark_stale<'a>(
+    conn: &mut Connection<'a>,
+    domain_name: &str,
+    stale_at: DateTime<Utc>,
+    reason: StaleReason,
+    checkpoint: u64,
+) -> Result<Vec<(String, String)>> {
+    let stale: Vec<(i32, String, String)> = diesel::update(
+        offers::table
+            .filter(offers::domain_name.eq(domain_name))
+            .filter(offers::status.eq_any(OPEN_OFFER_STATUSES))
+            .filter(offers::updated_at.lt(stale_at)),
+    )
+    .set((
+        offers::status.eq(OfferStatus::Stale),
+        offers::stale_reason.eq(Some(reason.as_str())),
+        offers::updated_at.eq(stale_at),
+    ))
+    .returning((offers::id, offers::buyer, offers::coin_type))
+    .get_results(conn)
+    .await?;
+
+    for (id, _, _) in &stale {
+        changes::offer_changed(conn, *id, None, checkpoint).await?;
+    }
+
+    if !stale.is_empty() {
+        info!(
+            "Marked {} offers for domain {} as stale: {}",
+            stale.len(),
+            domain_name,
+            reason.as_str()
+        );
+    }
+
+    Ok(stale
+        .into_iter()
+        .map(|(_, buyer, coin_type)| (buyer, coin_type))
+        .collect())
+}
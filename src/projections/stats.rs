@@ -0,0 +1,222 @@
+use crate::models::Epoch;
+use crate::schema::epochs;
+use anyhow::Context;
+use diesel::internal::derives::multiconnection::chrono::{DateTime, Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Date, Timestamptz};
+use diesel_async::RunQueryDsl;
+use log::info;
+use std::collections::{BTreeMap, BTreeSet};
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+// Serializes rollup refreshes between the sequential pipelines, so the last one to refresh a period
+// always sees the committed writes of the other one
+const STATS_LOCK_ID: i64 = 0x5549_4e53_5354_4154;
+
+// Aggregates for the period [$2, $3) per coin, keyed by $1
+const STATS_SELECT: &str = "
+    SELECT
+        $1,
+        coins.coin_type,
+        (SELECT COUNT(*) FROM offers WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        (SELECT COUNT(*) FROM sales WHERE coin_type = coins.coin_type AND sale_type IN ('offer', 'counter-offer') AND created_at >= $2 AND created_at < $3),
+        (SELECT COUNT(*) FROM auctions WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        (SELECT COUNT(*) FROM sales WHERE coin_type = coins.coin_type AND sale_type = 'auction' AND created_at >= $2 AND created_at < $3),
+        (SELECT COUNT(DISTINCT buyer) FROM sales WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        (SELECT COUNT(DISTINCT seller) FROM sales WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        (SELECT COALESCE(SUM(price::NUMERIC), 0)::VARCHAR FROM sales WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        (SELECT (PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY price::NUMERIC))::VARCHAR FROM sales WHERE coin_type = coins.coin_type AND created_at >= $2 AND created_at < $3),
+        NOW()
+    FROM (
+        SELECT coin_type FROM offers WHERE created_at >= $2 AND created_at < $3
+        UNION SELECT coin_type FROM auctions WHERE created_at >= $2 AND created_at < $3
+        UNION SELECT coin_type FROM sales WHERE created_at >= $2 AND created_at < $3
+    ) coins
+";
+
+const STATS_UPDATE: &str = "
+    offers_placed = EXCLUDED.offers_placed,
+    offers_accepted = EXCLUDED.offers_accepted,
+    auctions_created = EXCLUDED.auctions_created,
+    auctions_finalized = EXCLUDED.auctions_finalized,
+    unique_buyers = EXCLUDED.unique_buyers,
+    unique_sellers = EXCLUDED.unique_sellers,
+    volume = EXCLUDED.volume,
+    median_sale_price = EXCLUDED.median_sale_price,
+    updated_at = EXCLUDED.updated_at
+";
+
+const STATS_COLUMNS: &str = "coin_type, offers_placed, offers_accepted, auctions_created, \
+    auctions_finalized, unique_buyers, unique_sellers, volume, median_sale_price, updated_at";
+
+#[derive(QueryableByName)]
+struct StatsDay {
+    #[diesel(sql_type = Date)]
+    day: NaiveDate,
+}
+
+/// Periods touched by a batch of sequential pipeline values
+#[derive(Default)]
+pub struct TouchedPeriods {
+    days: BTreeSet<NaiveDate>,
+    epochs: BTreeMap<u64, (DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl TouchedPeriods {
+    /// Records a checkpoint, and whether it carried marketplace data that changes the rollups
+    pub fn add(&mut self, epoch: u64, timestamp: DateTime<Utc>, has_data: bool) {
+        let (first, last) = self.epochs.entry(epoch).or_insert((timestamp, timestamp));
+        *first = (*first).min(timestamp);
+        *last = (*last).max(timestamp);
+
+        if has_data {
+            self.days.insert(timestamp.date_naive());
+        }
+    }
+}
+
+/// Extends the known time range of the touched epochs and recomputes the touched rollups
+pub async fn refresh<'a>(conn: &mut Connection<'a>, periods: &TouchedPeriods) -> Result<()> {
+    if periods.epochs.is_empty() {
+        return Ok(());
+    }
+
+    diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+        .bind::<BigInt, _>(STATS_LOCK_ID)
+        .execute(conn)
+        .await?;
+
+    for (epoch, (first, last)) in &periods.epochs {
+        track_epoch(conn, *epoch, *first, *last).await?;
+    }
+
+    for day in &periods.days {
+        refresh_day(conn, *day).await?;
+    }
+
+    // Epochs only need a refresh when one of their days changed
+    for (epoch, (first, last)) in &periods.epochs {
+        if periods
+            .days
+            .range(first.date_naive()..=last.date_naive())
+            .next()
+            .is_some()
+        {
+            refresh_epoch(conn, *epoch).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn track_epoch<'a>(
+    conn: &mut Connection<'a>,
+    epoch: u64,
+    first_checkpoint_at: DateTime<Utc>,
+    last_checkpoint_at: DateTime<Utc>,
+) -> Result<()> {
+    diesel::insert_into(epochs::table)
+        .values(Epoch {
+            epoch: epoch as i64,
+            first_checkpoint_at,
+            last_checkpoint_at,
+        })
+        .on_conflict(epochs::epoch)
+        .do_update()
+        .set((
+            epochs::first_checkpoint_at.eq(diesel::dsl::sql::<Timestamptz>(
+                "LEAST(epochs.first_checkpoint_at, EXCLUDED.first_checkpoint_at)",
+            )),
+            epochs::last_checkpoint_at.eq(diesel::dsl::sql::<Timestamptz>(
+                "GREATEST(epochs.last_checkpoint_at, EXCLUDED.last_checkpoint_at)",
+            )),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Recomputes the rollup of a single UTC day
+pub async fn refresh_day<'a>(conn: &mut Connection<'a>, day: NaiveDate) -> Result<()> {
+    let start = day.and_hms_opt(0, 0, 0).context("invalid day")?.and_utc();
+    let end = start + Duration::days(1);
+
+    diesel::sql_query(format!(
+        "INSERT INTO daily_stats (day, {STATS_COLUMNS}) {STATS_SELECT} \
+         ON CONFLICT (day, coin_type) DO UPDATE SET {STATS_UPDATE}"
+    ))
+    .bind::<Date, _>(day)
+    .bind::<Timestamptz, _>(start)
+    .bind::<Timestamptz, _>(end)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Recomputes the rollup of an epoch, over the time range its checkpoints were seen in
+pub async fn refresh_epoch<'a>(conn: &mut Connection<'a>, epoch: u64) -> Result<()> {
+    let Some(bounds) = epochs::table
+        .find(epoch as i64)
+        .select(Epoch::as_select())
+        .first(conn)
+        .await
+        .optional()?
+    else {
+        return Ok(());
+    };
+
+    // Checkpoint timestamps have millisecond precision, so this makes the range inclusive
+    let end = bounds.last_checkpoint_at + Duration::milliseconds(1);
+
+    diesel::sql_query(format!(
+        "INSERT INTO epoch_stats (epoch, {STATS_COLUMNS}) {STATS_SELECT} \
+         ON CONFLICT (epoch, coin_type) DO UPDATE SET {STATS_UPDATE}"
+    ))
+    .bind::<BigInt, _>(epoch as i64)
+    .bind::<Timestamptz, _>(bounds.first_checkpoint_at)
+    .bind::<Timestamptz, _>(end)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Rebuilds every rollup from the tables indexed so far. Epoch rollups can only be rebuilt for
+/// epochs whose time range was recorded by the pipelines.
+///
+/// Accepted offers and finalized auctions are counted from `sales`, so this relies on the sales
+/// migration having backfilled the sales indexed before that table existed.
+pub async fn backfill<'a>(conn: &mut Connection<'a>) -> Result<()> {
+    let days: Vec<StatsDay> = diesel::sql_query(
+        "SELECT DISTINCT (created_at AT TIME ZONE 'UTC')::DATE AS day FROM (
+            SELECT created_at FROM offers
+            UNION ALL SELECT created_at FROM auctions
+            UNION ALL SELECT created_at FROM sales
+        ) t ORDER BY day",
+    )
+    .load(conn)
+    .await?;
+
+    info!("Backfilling stats for {} days", days.len());
+
+    for StatsDay { day } in days {
+        refresh_day(conn, day).await?;
+    }
+
+    let epochs: Vec<i64> = epochs::table
+        .select(epochs::epoch)
+        .order(epochs::epoch.asc())
+        .load(conn)
+        .await?;
+
+    info!("Backfilling stats for {} epochs", epochs.len());
+
+    for epoch in epochs {
+        refresh_epoch(conn, epoch as u64).await?;
+    }
+
+    Ok(())
+}
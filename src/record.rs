@@ -0,0 +1,96 @@
+use crate::checkpoints::{self, CheckpointSource};
+use crate::handlers::transactions_handler;
+use crate::registry;
+use anyhow::Context;
+use futures::{StreamExt, TryStreamExt};
+use log::info;
+use std::fs;
+use std::path::PathBuf;
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use sui_indexer_alt_framework::Result;
+
+/// Settings of the recording of checkpoints
+#[derive(clap::Args, Debug, Clone)]
+pub struct RecordArgs {
+    #[clap(flatten)]
+    pub source: CheckpointSource,
+
+    /// Directory to write the checkpoint files to
+    #[clap(long)]
+    pub output: PathBuf,
+
+    /// Checkpoints fetched at once
+    #[clap(long, default_value_t = 10)]
+    pub concurrency: usize,
+}
+
+/// Copies the checkpoints of the source with transactions of the marketplace package, or with
+/// objects of the SuiNS package when it's given, to the output directory, as `<sequence number>.chk`
+/// files.
+/// Other checkpoints are skipped, so the directory has gaps: the framework's local ingestion stops
+/// at the first one, and `CheckpointSource` with `--checkpoint-dir` is the only supported reader.
+pub async fn run(
+    args: &RecordArgs,
+    contract_package_id: &str,
+    suins_package_id: Option<&str>,
+) -> Result<()> {
+    fs::create_dir_all(&args.output)
+        .with_context(|| format!("failed to create {}", args.output.display()))?;
+
+    let client = reqwest::Client::new();
+    let sequence_numbers = args.source.sequence_numbers()?;
+    info!("Reading {} checkpoints", sequence_numbers.len());
+
+    let mut checkpoints = futures::stream::iter(sequence_numbers)
+        .map(|sequence_number| args.source.read(&client, sequence_number))
+        .buffered(args.concurrency.max(1));
+
+    let mut recorded = 0;
+    while let Some(checkpoint) = checkpoints.try_next().await? {
+        if !has_package_transactions(&checkpoint, contract_package_id)
+            && !suins_package_id.is_some_and(|id| has_registry_objects(&checkpoint, id))
+        {
+            continue;
+        }
+
+        let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+        let path = checkpoints::file_path(&args.output, sequence_number);
+        fs::write(&path, checkpoints::encode(&checkpoint)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        info!("Recorded checkpoint {}", sequence_number);
+        recorded += 1;
+    }
+
+    info!(
+        "Recorded {} checkpoints to {}",
+        recorded,
+        args.output.display()
+    );
+    Ok(())
+}
+
+// Same tests as the pipelines use to pick the events and transactions of the marketplace
+fn has_package_transactions(checkpoint: &CheckpointData, contract_package_id: &str) -> bool {
+    checkpoint.transactions.iter().any(|tx| {
+        tx.events
+            .iter()
+            .flat_map(|events| &events.data)
+            .any(|event| event.type_.to_string().starts_with(contract_package_id))
+            || transactions_handler::uses_package(tx, contract_package_id)
+    })
+}
+
+// Same tests as the registry pipelines use to pick name records and registration NFTs. Objects
+// that fail to decode are kept, so replaying the checkpoint reports them.
+fn has_registry_objects(checkpoint: &CheckpointData, suins_package_id: &str) -> bool {
+    checkpoint
+        .transactions
+        .iter()
+        .flat_map(|tx| tx.input_objects.iter().chain(&tx.output_objects))
+        .any(|object| {
+            let name_record = registry::try_name_record(object, suins_package_id);
+            let registration = registry::try_registration(object, suins_package_id);
+            !matches!(name_record, Ok(None)) || !matches!(registration, Ok(None))
+        })
+}
@@ -0,0 +1,136 @@
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde::Deserialize;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::object::Object;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+// Types of the SuiNS core package, as stored on chain
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Domain {
+    // Labels are stored from the top level domain down, e.g. `["sui", "example"]`
+    pub labels: Vec<String>,
+}
+
+impl Domain {
+    /// Name of the domain as used by the marketplace, e.g. `example.sui`
+    pub fn to_name(&self) -> String {
+        self.labels
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VecMap<K, V> {
+    pub contents: Vec<Entry<K, V>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Entry<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NameRecord {
+    pub nft_id: ObjectID,
+    pub expiration_timestamp_ms: u64,
+    pub target_address: Option<SuiAddress>,
+    pub data: VecMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Url {
+    pub url: String,
+}
+
+// The NFT proving ownership of a registered name
+#[derive(Deserialize, Debug, Clone)]
+pub struct SuinsRegistration {
+    pub id: ObjectID,
+    pub domain: Domain,
+    pub domain_name: String,
+    pub expiration_timestamp_ms: u64,
+    pub image_url: Url,
+}
+
+// Name records live in the registry as `0x2::dynamic_field::Field<Domain, NameRecord>` objects
+#[derive(Deserialize, Debug, Clone)]
+pub struct Field<K, V> {
+    pub id: ObjectID,
+    pub name: K,
+    pub value: V,
+}
+
+pub fn struct_tag(object: &Object) -> Option<StructTag> {
+    let move_object = object.data.try_as_move()?;
+    Some(move_object.type_().clone().into())
+}
+
+/// Returns the name record stored in a registry dynamic field of the given SuiNS package. Types are
+/// matched by their defining package, so `suins_package_id` has to be the original package ID in
+/// its full 64 hex digit form
+pub fn try_name_record(
+    object: &Object,
+    suins_package_id: &str,
+) -> anyhow::Result<Option<Field<Domain, NameRecord>>> {
+    let Some(tag) = struct_tag(object) else {
+        return Ok(None);
+    };
+
+    if tag.address != SUI_FRAMEWORK_ADDRESS
+        || tag.module.as_str() != "dynamic_field"
+        || tag.name.as_str() != "Field"
+    {
+        return Ok(None);
+    }
+
+    let [TypeTag::Struct(key), TypeTag::Struct(value)] = tag.type_params.as_slice() else {
+        return Ok(None);
+    };
+
+    if !key.to_string().starts_with(suins_package_id)
+        || key.name.as_str() != "Domain"
+        || value.name.as_str() != "NameRecord"
+    {
+        return Ok(None);
+    }
+
+    let Some(move_object) = object.data.try_as_move() else {
+        return Ok(None);
+    };
+
+    let field = bcs::from_bytes(move_object.contents())?;
+
+    Ok(Some(field))
+}
+
+/// Returns the registration NFT of the given SuiNS package held in the object, matched the same
+/// way as in `try_name_record`
+pub fn try_registration(
+    object: &Object,
+    suins_package_id: &str,
+) -> anyhow::Result<Option<SuinsRegistration>> {
+    let Some(tag) = struct_tag(object) else {
+        return Ok(None);
+    };
+
+    if !tag.to_string().starts_with(suins_package_id)
+        || tag.module.as_str() != "suins_registration"
+        || tag.name.as_str() != "SuinsRegistration"
+    {
+        return Ok(None);
+    }
+
+    let Some(move_object) = object.data.try_as_move() else {
+        return Ok(None);
+    };
+
+    let registration = bcs::from_bytes(move_object.contents())?;
+
+    Ok(Some(registration))
+}
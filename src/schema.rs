@@ -1,13 +1,33 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "activityrole"))]
+    pub struct Activityrole;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "auctionstatus"))]
     pub struct Auctionstatus;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "escrowdirection"))]
+    pub struct Escrowdirection;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "escrowkind"))]
+    pub struct Escrowkind;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "offerstatus"))]
     pub struct Offerstatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "ownerkind"))]
+    pub struct Ownerkind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "saletype"))]
+    pub struct Saletype;
 }
 
 diesel::table! {
@@ -18,6 +38,40 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Activityrole;
+
+    account_activity (id) {
+        id -> Int4,
+        address -> Varchar,
+        role -> Activityrole,
+        action -> Varchar,
+        domain_name -> Varchar,
+        counterparty -> Nullable<Varchar>,
+        amount -> Nullable<Varchar>,
+        checkpoint -> Int8,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
+        coin_type -> Varchar,
+    }
+}
+
+diesel::table! {
+    accounts (address, coin_type) {
+        address -> Varchar,
+        total_spent -> Varchar,
+        total_received -> Varchar,
+        open_commitments -> Varchar,
+        purchase_count -> Int4,
+        sale_count -> Int4,
+        updated_at -> Timestamptz,
+        coin_type -> Varchar,
     }
 }
 
@@ -38,6 +92,37 @@ diesel::table! {
         updated_at -> Timestamptz,
         created_at -> Timestamptz,
         last_tx_digest -> Varchar,
+        invalid_bid_count -> Int4,
+        coin_type -> Varchar,
+        locked_value -> Varchar,
+        highest_bid -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Auctionstatus;
+
+    auctions_history (history_id) {
+        history_id -> Int4,
+        auction_id -> Varchar,
+        domain_name -> Varchar,
+        owner -> Varchar,
+        start_time -> Int8,
+        end_time -> Int8,
+        min_bid -> Varchar,
+        winner -> Nullable<Varchar>,
+        amount -> Nullable<Varchar>,
+        status -> Auctionstatus,
+        updated_at -> Timestamptz,
+        created_at -> Timestamptz,
+        last_tx_digest -> Varchar,
+        invalid_bid_count -> Int4,
+        coin_type -> Varchar,
+        locked_value -> Varchar,
+        highest_bid -> Nullable<Varchar>,
+        valid_from_checkpoint -> Int8,
+        valid_to_checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -50,6 +135,103 @@ diesel::table! {
         amount -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        is_valid -> Bool,
+        violations -> Array<Text>,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
+        event_seq -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    daily_stats (day, coin_type) {
+        day -> Date,
+        offers_placed -> Int4,
+        offers_accepted -> Int4,
+        auctions_created -> Int4,
+        auctions_finalized -> Int4,
+        unique_buyers -> Int4,
+        unique_sellers -> Int4,
+        volume -> Varchar,
+        median_sale_price -> Nullable<Varchar>,
+        updated_at -> Timestamptz,
+        coin_type -> Varchar,
+    }
+}
+
+diesel::table! {
+    domain_coin_stats (domain_name, coin_type) {
+        domain_name -> Varchar,
+        coin_type -> Varchar,
+        open_offer_count -> Int4,
+        best_offer -> Nullable<Varchar>,
+        total_volume -> Varchar,
+        sale_count -> Int4,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    domains (domain_name) {
+        domain_name -> Varchar,
+        open_offer_count -> Int4,
+        active_auction_id -> Nullable<Varchar>,
+        last_sale_price -> Nullable<Varchar>,
+        last_sale_at -> Nullable<Timestamptz>,
+        sale_count -> Int4,
+        updated_at -> Timestamptz,
+        label_length -> Nullable<Int4>,
+        char_class -> Nullable<Varchar>,
+        is_palindrome -> Nullable<Bool>,
+        has_repeating_pattern -> Nullable<Bool>,
+        is_subdomain -> Nullable<Bool>,
+        is_dictionary_word -> Nullable<Bool>,
+        last_sale_coin_type -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    epoch_stats (epoch, coin_type) {
+        epoch -> Int8,
+        offers_placed -> Int4,
+        offers_accepted -> Int4,
+        auctions_created -> Int4,
+        auctions_finalized -> Int4,
+        unique_buyers -> Int4,
+        unique_sellers -> Int4,
+        volume -> Varchar,
+        median_sale_price -> Nullable<Varchar>,
+        updated_at -> Timestamptz,
+        coin_type -> Varchar,
+    }
+}
+
+diesel::table! {
+    epochs (epoch) {
+        epoch -> Int8,
+        first_checkpoint_at -> Timestamptz,
+        last_checkpoint_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Escrowkind;
+    use super::sql_types::Escrowdirection;
+
+    escrow_movements (id) {
+        id -> Int4,
+        escrow_kind -> Escrowkind,
+        reference -> Varchar,
+        address -> Varchar,
+        domain_name -> Varchar,
+        direction -> Escrowdirection,
+        reason -> Varchar,
+        amount -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Int8,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
     }
 }
 
@@ -62,6 +244,59 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    market_events (id) {
+        id -> Int8,
+        event_type -> Varchar,
+        domain_name -> Varchar,
+        addresses -> Array<Varchar>,
+        data -> Jsonb,
+        coin_type -> Varchar,
+        checkpoint -> Int8,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
+        event_seq -> Int8,
+    }
+}
+
+diesel::table! {
+    name_records (domain_name) {
+        domain_name -> Varchar,
+        nft_id -> Varchar,
+        target_address -> Nullable<Varchar>,
+        expiration_timestamp_ms -> Int8,
+        expires_at -> Timestamptz,
+        registered_at -> Timestamptz,
+        last_renewed_at -> Nullable<Timestamptz>,
+        removed_at -> Nullable<Timestamptz>,
+        checkpoint -> Int8,
+        last_tx_digest -> Varchar,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Activityrole;
+
+    negotiation_rounds (id) {
+        id -> Int4,
+        offer_id -> Int4,
+        round -> Int4,
+        domain_name -> Varchar,
+        proposer -> Varchar,
+        proposer_role -> Activityrole,
+        action -> Varchar,
+        amount -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Int8,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
     }
 }
 
@@ -74,6 +309,8 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -85,6 +322,8 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -97,6 +336,8 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -108,6 +349,8 @@ diesel::table! {
         value -> Varchar,
         created_at -> Timestamptz,
         tx_digest -> Varchar,
+        coin_type -> Varchar,
+        checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -126,6 +369,101 @@ diesel::table! {
         updated_at -> Timestamptz,
         created_at -> Timestamptz,
         last_tx_digest -> Varchar,
+        coin_type -> Varchar,
+        stale_reason -> Nullable<Varchar>,
+        locked_value -> Varchar,
+        buyer_value -> Varchar,
+        counter_value -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Offerstatus;
+
+    offers_history (history_id) {
+        history_id -> Int4,
+        offer_id -> Int4,
+        domain_name -> Varchar,
+        buyer -> Varchar,
+        initial_value -> Varchar,
+        value -> Varchar,
+        owner -> Nullable<Varchar>,
+        status -> Offerstatus,
+        updated_at -> Timestamptz,
+        created_at -> Timestamptz,
+        last_tx_digest -> Varchar,
+        coin_type -> Varchar,
+        stale_reason -> Nullable<Varchar>,
+        locked_value -> Varchar,
+        buyer_value -> Varchar,
+        counter_value -> Nullable<Varchar>,
+        valid_from_checkpoint -> Int8,
+        valid_to_checkpoint -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Saletype;
+
+    sales (id) {
+        id -> Int4,
+        domain_name -> Varchar,
+        seller -> Varchar,
+        buyer -> Varchar,
+        price -> Varchar,
+        sale_type -> Saletype,
+        checkpoint -> Nullable<Int8>,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
+        coin_type -> Varchar,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Ownerkind;
+
+    registration_owners (nft_id) {
+        nft_id -> Varchar,
+        domain_name -> Varchar,
+        owner_kind -> Ownerkind,
+        owner -> Nullable<Varchar>,
+        expiration_timestamp_ms -> Int8,
+        checkpoint -> Int8,
+        last_tx_digest -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        holder -> Nullable<Varchar>,
+        transferred_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    sink_cursors (sink) {
+        sink -> Varchar,
+        checkpoint -> Int8,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    transactions (tx_digest) {
+        tx_digest -> Varchar,
+        sender -> Varchar,
+        gas_budget -> Varchar,
+        gas_price -> Varchar,
+        computation_cost -> Varchar,
+        storage_cost -> Varchar,
+        storage_rebate -> Varchar,
+        non_refundable_storage_fee -> Varchar,
+        status -> Varchar,
+        error -> Nullable<Varchar>,
+        event_count -> Int4,
+        checkpoint -> Int8,
+        epoch -> Int8,
+        created_at -> Timestamptz,
     }
 }
 
@@ -142,17 +480,79 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webhook_outbox (id) {
+        id -> Int8,
+        subscription_id -> Int4,
+        event_type -> Varchar,
+        domain_name -> Varchar,
+        payload -> Jsonb,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_status_code -> Nullable<Int4>,
+        last_error -> Nullable<Varchar>,
+        delivered_at -> Nullable<Timestamptz>,
+        failed_at -> Nullable<Timestamptz>,
+        checkpoint -> Int8,
+        tx_digest -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    webhook_subscriptions (id) {
+        id -> Int4,
+        url -> Varchar,
+        event_types -> Array<Varchar>,
+        domain_name -> Nullable<Varchar>,
+        address -> Nullable<Varchar>,
+        secret -> Varchar,
+        active -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(accept_counter_offer -> transactions (tx_digest));
+diesel::joinable!(auctions_history -> auctions (auction_id));
 diesel::joinable!(bids -> auctions (auction_id));
+diesel::joinable!(make_counter_offer -> transactions (tx_digest));
+diesel::joinable!(negotiation_rounds -> offers (offer_id));
+diesel::joinable!(offer_accepted -> transactions (tx_digest));
+diesel::joinable!(offer_cancelled -> transactions (tx_digest));
+diesel::joinable!(offer_declined -> transactions (tx_digest));
+diesel::joinable!(offer_placed -> transactions (tx_digest));
+diesel::joinable!(offers_history -> offers (offer_id));
+diesel::joinable!(sales -> transactions (tx_digest));
+diesel::joinable!(webhook_outbox -> webhook_subscriptions (subscription_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     accept_counter_offer,
+    account_activity,
+    accounts,
     auctions,
+    auctions_history,
     bids,
+    daily_stats,
+    domain_coin_stats,
+    domains,
+    epoch_stats,
+    epochs,
+    escrow_movements,
     make_counter_offer,
+    market_events,
+    name_records,
+    negotiation_rounds,
     offer_accepted,
     offer_cancelled,
     offer_declined,
     offer_placed,
     offers,
+    offers_history,
+    registration_owners,
+    sales,
+    sink_cursors,
+    transactions,
     watermarks,
+    webhook_outbox,
+    webhook_subscriptions,
 );
@@ -0,0 +1,419 @@
+use crate::feed::FEED_PIPELINES;
+use crate::models::MarketEvent;
+use crate::schema::{market_events, sink_cursors, watermarks};
+use anyhow::Context;
+use diesel::internal::derives::multiconnection::chrono::Utc;
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use log::{info, warn};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_indexer_alt_framework::postgres::{Connection, Db};
+use sui_indexer_alt_framework::Result;
+
+/// A destination of the marketplace events published by the pipelines to `market_events`.
+///
+/// Sinks are handed the events of committed checkpoints by `run`, in publication order, from a
+/// blocking thread. A sink returning an error is handed the events again from the first checkpoint
+/// it didn't receive all the events of, so events are delivered at least once. The position of
+/// every sink is kept under its name, and events handed again keep their `id`, so consumers
+/// deduplicate them by it, or by `tx_digest` and `event_seq`.
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn publish(&self, event: &MarketEvent) -> anyhow::Result<()>;
+}
+
+/// Settings of the delivery of market events to the sinks
+#[derive(clap::Args, Debug, Clone)]
+pub struct SinkArgs {
+    /// Wait between polls of the events table when the sinks are caught up, or after one failed
+    #[clap(long = "sink-poll-interval-secs", default_value_t = 1)]
+    pub poll_interval_secs: u64,
+
+    /// Checkpoints read per poll, so sinks far behind catch up in steps
+    #[clap(long = "sink-checkpoint-span", default_value_t = 10_000)]
+    pub checkpoint_span: i64,
+}
+
+/// Hands the events committed by the pipelines to the sinks, until the process is stopped. A
+/// failing sink is retried after the poll interval, without holding back the others.
+pub async fn run(db: Db, sinks: Vec<Arc<dyn EventSink>>, args: SinkArgs) -> Result<()> {
+    for sink in &sinks {
+        info!("Publishing market events to sink {}", sink.name());
+    }
+
+    loop {
+        let mut moved = false;
+        let mut failed = false;
+
+        for sink in &sinks {
+            let result = match db.connect().await {
+                Ok(mut conn) => drain(&mut conn, sink, &args).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(drained) => moved |= drained,
+                Err(e) => {
+                    warn!(
+                        "Failed to publish market events to sink {}: {:#}",
+                        sink.name(),
+                        e
+                    );
+                    failed = true;
+                }
+            }
+        }
+
+        if failed || !moved {
+            tokio::time::sleep(Duration::from_secs(args.poll_interval_secs)).await;
+        }
+    }
+}
+
+/// Hands the sink the events of the checkpoints committed after its position, up to
+/// `checkpoint_span` of them, and moves its position past those it received. Returns whether the
+/// position moved. New sinks start from the latest committed checkpoint.
+pub async fn drain<'a>(
+    conn: &mut Connection<'a>,
+    sink: &Arc<dyn EventSink>,
+    args: &SinkArgs,
+) -> Result<bool> {
+    let watermarks: Vec<i64> = watermarks::table
+        .filter(watermarks::pipeline.eq_any(FEED_PIPELINES))
+        .select(watermarks::checkpoint_hi_inclusive)
+        .load(conn)
+        .await?;
+
+    // Nothing is published until every pipeline has committed something
+    if watermarks.len() < FEED_PIPELINES.len() {
+        return Ok(false);
+    }
+    let Some(committed) = watermarks.into_iter().min() else {
+        return Ok(false);
+    };
+
+    let cursor: Option<i64> = sink_cursors::table
+        .find(sink.name())
+        .select(sink_cursors::checkpoint)
+        .first(conn)
+        .await
+        .optional()?;
+
+    let Some(cursor) = cursor else {
+        save_cursor(conn, sink.name(), committed).await?;
+        return Ok(true);
+    };
+
+    if committed <= cursor {
+        return Ok(false);
+    }
+
+    let hi = committed.min(cursor.saturating_add(args.checkpoint_span));
+
+    let events: Vec<MarketEvent> = market_events::table
+        .filter(market_events::checkpoint.gt(cursor))
+        .filter(market_events::checkpoint.le(hi))
+        .order((market_events::checkpoint.asc(), market_events::id.asc()))
+        .select(MarketEvent::as_select())
+        .load(conn)
+        .await?;
+
+    let publisher = sink.clone();
+    let (received, result) =
+        tokio::task::spawn_blocking(move || publish_events(&*publisher, &events, cursor, hi))
+            .await
+            .context("event sink panicked")?;
+
+    if received > cursor {
+        save_cursor(conn, sink.name(), received).await?;
+    }
+    result?;
+
+    Ok(received > cursor)
+}
+
+// Hands the events of the checkpoints after `cursor`, up to `hi`, to the sink in order. Returns
+// the last checkpoint the sink received all the events of, along with the error that stopped it.
+fn publish_events(
+    sink: &dyn EventSink,
+    events: &[MarketEvent],
+    cursor: i64,
+    hi: i64,
+) -> (i64, anyhow::Result<()>) {
+    for event in events {
+        if let Err(e) = sink.publish(event) {
+            let error = e.context(format!("failed to publish event {}", event.id));
+            return (cursor.max(event.checkpoint - 1), Err(error));
+        }
+    }
+
+    (hi, Ok(()))
+}
+
+async fn save_cursor<'a>(conn: &mut Connection<'a>, sink: &str, checkpoint: i64) -> Result<()> {
+    diesel::insert_into(sink_cursors::table)
+        .values((
+            sink_cursors::sink.eq(sink),
+            sink_cursors::checkpoint.eq(checkpoint),
+            sink_cursors::updated_at.eq(Utc::now()),
+        ))
+        .on_conflict(sink_cursors::sink)
+        .do_update()
+        .set((
+            sink_cursors::checkpoint.eq(checkpoint),
+            sink_cursors::updated_at.eq(Utc::now()),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Writes events as JSON lines to files in a directory, starting a new file once the current one
+/// reaches `max_bytes`. Files are named after the checkpoint and id of their first event, so they
+/// sort in publication order.
+pub struct JsonlSink {
+    directory: PathBuf,
+    max_bytes: u64,
+    current: Mutex<Option<(File, u64)>>,
+}
+
+impl JsonlSink {
+    pub fn new(directory: PathBuf, max_bytes: u64) -> anyhow::Result<Self> {
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("failed to create {}", directory.display()))?;
+
+        Ok(Self {
+            directory,
+            max_bytes,
+            current: Mutex::new(None),
+        })
+    }
+
+    fn open(&self, event: &MarketEvent) -> anyhow::Result<File> {
+        let path = self.directory.join(format!(
+            "market-events-{:020}-{:020}.jsonl",
+            event.checkpoint, event.id
+        ));
+
+        info!("Writing market events to {}", path.display());
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))
+    }
+}
+
+impl EventSink for JsonlSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn publish(&self, event: &MarketEvent) -> anyhow::Result<()> {
+        let mut line = event.to_json().to_string();
+        line.push('\n');
+
+        let mut current = self.current.lock().expect("jsonl sink lock poisoned");
+
+        let rotate = match current.as_ref() {
+            Some((_, written)) => *written > 0 && written + line.len() as u64 > self.max_bytes,
+            None => true,
+        };
+        if rotate {
+            *current = Some((self.open(event)?, 0));
+        }
+
+        let (file, written) = current.as_mut().expect("jsonl sink file is open");
+        file.write_all(line.as_bytes())
+            .context("failed to write market event")?;
+        *written += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Keeps published events in memory, for tests
+#[derive(Default)]
+pub struct MemorySink {
+    events: Mutex<Vec<MarketEvent>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events published so far, in publication order
+    pub fn events(&self) -> Vec<MarketEvent> {
+        self.events.lock().expect("memory sink lock poisoned").clone()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().expect("memory sink lock poisoned").clear();
+    }
+}
+
+impl EventSink for MemorySink {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn publish(&self, event: &MarketEvent) -> anyhow::Result<()> {
+        self.events
+            .lock()
+            .expect("memory sink lock poisoned")
+            .push(event.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::internal::derives::multiconnection::chrono::{DateTime, Utc};
+
+    fn event(id: i64, tx_digest: &str, event_seq: i64) -> MarketEvent {
+        MarketEvent {
+            id,
+            event_type: "offer-placed".to_string(),
+            domain_name: "example.sui".to_string(),
+            addresses: vec!["0x1".to_string()],
+            data: serde_json::json!({ "value": "100" }),
+            coin_type: "0x2::sui::SUI".to_string(),
+            checkpoint: 7,
+            tx_digest: tx_digest.to_string(),
+            created_at: DateTime::<Utc>::from_timestamp_millis(0).unwrap(),
+            event_seq,
+        }
+    }
+
+    // Fails once on the event with the given id, and keeps the other events in memory
+    struct FlakySink {
+        fail_on: Mutex<Option<i64>>,
+        events: MemorySink,
+    }
+
+    impl EventSink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn publish(&self, event: &MarketEvent) -> anyhow::Result<()> {
+            let mut fail_on = self.fail_on.lock().unwrap();
+            if *fail_on == Some(event.id) {
+                *fail_on = None;
+                anyhow::bail!("write failed");
+            }
+
+            self.events.publish(event)
+        }
+    }
+
+    #[test]
+    fn failed_write_is_retried() {
+        let sink = FlakySink {
+            fail_on: Mutex::new(Some(3)),
+            events: MemorySink::new(),
+        };
+        let events = vec![
+            event(1, "tx1", 0),
+            event(2, "tx1", 1),
+            MarketEvent {
+                checkpoint: 8,
+                ..event(3, "tx2", 0)
+            },
+            MarketEvent {
+                checkpoint: 9,
+                ..event(4, "tx3", 0)
+            },
+        ];
+
+        // Checkpoint 7 was received in full before the write failed
+        let (received, result) = publish_events(&sink, &events, 6, 9);
+        assert_eq!(received, 7);
+        assert!(result.is_err());
+
+        let remaining: Vec<MarketEvent> = events
+            .into_iter()
+            .filter(|event| event.checkpoint > received)
+            .collect();
+        let (received, result) = publish_events(&sink, &remaining, received, 9);
+        assert_eq!(received, 9);
+        assert!(result.is_ok());
+
+        assert_eq!(
+            sink.events
+                .events()
+                .iter()
+                .map(|event| event.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn jsonl_lines_keep_event_identity() {
+        let directory = std::env::temp_dir().join(format!("jsonl-sink-{}", std::process::id()));
+        // Every file holds a single line
+        let sink = JsonlSink::new(directory.clone(), 1).unwrap();
+
+        // Events of the same transaction are told apart by their index in it
+        for event in [
+            event(1, "tx1", 0),
+            event(2, "tx1", 2),
+            event(3, "tx1", 0),
+            event(4, "tx1", 2),
+        ] {
+            sink.publish(&event).unwrap();
+        }
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+
+        let lines: Vec<serde_json::Value> = files
+            .iter()
+            .flat_map(|file| {
+                fs::read_to_string(file)
+                    .unwrap()
+                    .lines()
+                    .map(|line| serde_json::from_str(line).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(files.len(), 4);
+        let keys: Vec<(i64, String, i64)> = lines
+            .iter()
+            .map(|line| {
+                (
+                    line["id"].as_i64().unwrap(),
+                    line["tx_digest"].as_str().unwrap().to_string(),
+                    line["event_seq"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                (1, "tx1".to_string(), 0),
+                (2, "tx1".to_string(), 2),
+                (3, "tx1".to_string(), 0),
+                (4, "tx1".to_string(), 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::models::{MarketEvent, NewWebhookDelivery, WebhookDelivery, WebhookSubscription};
+use crate::schema::{webhook_outbox, webhook_subscriptions};
+use anyhow::Context;
+use diesel::internal::derives::multiconnection::chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use sha2::Sha256;
+use sui_indexer_alt_framework::postgres::Connection;
+use sui_indexer_alt_framework::Result;
+
+/// Header with the id of the delivery, the same across retries so receivers can deduplicate
+pub const ID_HEADER: &str = "X-Webhook-Id";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+/// Header with `sha256=` followed by the hex HMAC of `<timestamp>.<body>`, see `sign`
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Market events partners can subscribe to, out of everything published to `market_events`
+pub const WEBHOOK_EVENT_TYPES: [&str; 5] = [
+    "offer-placed",
+    "offer-accepted",
+    "counter-offer-accepted",
+    "auction-created",
+    "auction-finalized",
+];
+
+/// Settings of the webhook delivery worker
+#[derive(clap::Args, Debug, Clone)]
+pub struct DeliveryArgs {
+    /// Deliveries attempted per poll of the outbox
+    #[clap(long, default_value_t = 100)]
+    pub batch_size: i64,
+
+    /// Attempts after which a delivery is given up and marked as failed
+    #[clap(long, default_value_t = 10)]
+    pub max_attempts: i32,
+
+    /// Delay before the first retry, doubled after every failed attempt
+    #[clap(long, default_value_t = 10)]
+    pub retry_base_secs: i64,
+
+    /// Upper bound of the delay between retries
+    #[clap(long, default_value_t = 3600)]
+    pub retry_max_secs: i64,
+
+    #[clap(long, default_value_t = 10)]
+    pub request_timeout_secs: u64,
+
+    /// Wait between polls when the outbox has nothing due
+    #[clap(long, default_value_t = 5)]
+    pub poll_interval_secs: u64,
+}
+
+/// Adds a delivery of the event to the outbox for every active subscription it matches, in the
+/// transaction that publishes the event
+pub async fn enqueue<'a>(conn: &mut Connection<'a>, event: &MarketEvent) -> Result<usize> {
+    if !WEBHOOK_EVENT_TYPES.contains(&event.event_type.as_str()) {
+        return Ok(0);
+    }
+
+    let subscription_ids: Vec<i32> = webhook_subscriptions::table
+        .filter(webhook_subscriptions::active.eq(true))
+        .filter(
+            webhook_subscriptions::event_types
+                .eq(Vec::<String>::new())
+                .or(webhook_subscriptions::event_types.contains(vec![event.event_type.clone()])),
+        )
+        .filter(
+            webhook_subscriptions::domain_name
+                .is_null()
+                .or(webhook_subscriptions::domain_name.eq(&event.domain_name)),
+        )
+        .filter(
+            webhook_subscriptions::address
+                .is_null()
+                .or(webhook_subscriptions::address.eq_any(&event.addresses)),
+        )
+        .select(webhook_subscriptions::id)
+        .load(conn)
+        .await?;
+
+    if subscription_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let payload = event.to_json();
+    let deliveries: Vec<NewWebhookDelivery> = subscription_ids
+        .into_iter()
+        .map(|subscription_id| NewWebhookDelivery {
+            subscription_id,
+            event_type: event.event_type.clone(),
+            domain_name: event.domain_name.clone(),
+            payload: payload.clone(),
+            next_attempt_at: event.created_at,
+            checkpoint: event.checkpoint,
+            tx_digest: event.tx_digest.clone(),
+            created_at: event.created_at,
+        })
+        .collect();
+
+    let count = diesel::insert_into(webhook_outbox::table)
+        .values(&deliveries)
+        .execute(conn)
+        .await?;
+
+    Ok(count)
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>` with the secret of the subscription. Receivers
+/// recompute it to check that a request comes from the indexer and was not replayed later.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Delay before the next attempt of a delivery that failed `attempts` times
+pub fn retry_delay(attempts: i32, args: &DeliveryArgs) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    let secs = args
+        .retry_base_secs
+        .saturating_mul(1i64 << exponent)
+        .min(args.retry_max_secs);
+    Duration::seconds(secs)
+}
+
+/// Delivers the outbox forever, polling it whenever nothing is due. Several workers can share an
+/// outbox, see `deliver_due`.
+pub async fn run<'a>(conn: &mut Connection<'a>, args: &DeliveryArgs) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(args.request_timeout_secs))
+        .build()
+        .context("failed to build HTTP client")?;
+
+    loop {
+        let attempted = deliver_due(conn, &client, args).await?;
+        if attempted == 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(args.poll_interval_secs)).await;
+        }
+    }
+}
+
+/// Attempts every delivery that is due, oldest first, and records the outcome. Returns the number
+/// of deliveries attempted.
+///
+/// Deliveries are claimed before being attempted, by moving their next attempt past the time the
+/// whole batch can take, so other workers skip them. A worker that stops mid-batch leaves its
+/// remaining deliveries to be retried once that time has passed.
+pub async fn deliver_due<'a>(
+    conn: &mut Connection<'a>,
+    client: &reqwest::Client,
+    args: &DeliveryArgs,
+) -> Result<usize> {
+    let now = Utc::now();
+    let claimed_until = now
+        + Duration::seconds(
+            (args.request_timeout_secs as i64 + 1).saturating_mul(args.batch_size.max(1)),
+        );
+
+    // Rows claimed concurrently by another worker are skipped rather than waited for
+    let due_ids = webhook_outbox::table
+        .filter(webhook_outbox::delivered_at.is_null())
+        .filter(webhook_outbox::failed_at.is_null())
+        .filter(webhook_outbox::next_attempt_at.le(now))
+        .filter(
+            webhook_outbox::subscription_id.eq_any(
+                webhook_subscriptions::table
+                    .filter(webhook_subscriptions::active.eq(true))
+                    .select(webhook_subscriptions::id),
+            ),
+        )
+        .order(webhook_outbox::id.asc())
+        .limit(args.batch_size)
+        .select(webhook_outbox::id)
+        .for_update()
+        .skip_locked();
+
+    let mut due: Vec<WebhookDelivery> =
+        diesel::update(webhook_outbox::table.filter(webhook_outbox::id.eq_any(due_ids)))
+            .set(webhook_outbox::next_attempt_at.eq(claimed_until))
+            .returning(WebhookDelivery::as_returning())
+            .get_results(conn)
+            .await?;
+    due.sort_by_key(|delivery| delivery.id);
+
+    let attempted = due.len();
+
+    for delivery in due {
+        let subscription: WebhookSubscription = webhook_subscriptions::table
+            .find(delivery.subscription_id)
+            .select(WebhookSubscription::as_select())
+            .first(conn)
+            .await?;
+
+        let (status_code, error) = post(client, &delivery, &subscription).await;
+        let attempts = delivery.attempts + 1;
+        let now = Utc::now();
+
+        let target = webhook_outbox::table.filter(webhook_outbox::id.eq(delivery.id));
+
+        match error {
+            None => {
+                info!(
+                    "Delivered {} webhook {} to {}",
+                    delivery.event_type, delivery.id, subscription.url
+                );
+
+                diesel::update(target)
+                    .set((
+                        webhook_outbox::attempts.eq(attempts),
+                        webhook_outbox::last_status_code.eq(status_code),
+                        webhook_outbox::last_error.eq(None::<String>),
+                        webhook_outbox::delivered_at.eq(Some(now)),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            Some(error) if attempts >= args.max_attempts => {
+                warn!(
+                    "Giving up {} webhook {} to {} after {} attempts: {}",
+                    delivery.event_type, delivery.id, subscription.url, attempts, error
+                );
+
+                diesel::update(target)
+                    .set((
+                        webhook_outbox::attempts.eq(attempts),
+                        webhook_outbox::last_status_code.eq(status_code),
+                        webhook_outbox::last_error.eq(Some(error)),
+                        webhook_outbox::failed_at.eq(Some(now)),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            Some(error) => {
+                let next_attempt_at = now + retry_delay(attempts, args);
+
+                warn!(
+                    "Failed {} webhook {} to {}, retrying at {}: {}",
+                    delivery.event_type, delivery.id, subscription.url, next_attempt_at, error
+                );
+
+                diesel::update(target)
+                    .set((
+                        webhook_outbox::attempts.eq(attempts),
+                        webhook_outbox::last_status_code.eq(status_code),
+                        webhook_outbox::last_error.eq(Some(error)),
+                        webhook_outbox::next_attempt_at.eq(next_attempt_at),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+        }
+    }
+
+    Ok(attempted)
+}
+
+// Returns the status code of the response, if any, and an error unless it was a success
+async fn post(
+    client: &reqwest::Client,
+    delivery: &WebhookDelivery,
+    subscription: &WebhookSubscription,
+) -> (Option<i32>, Option<String>) {
+    let body = delivery.payload.to_string();
+    let timestamp = Utc::now().timestamp();
+    let signature = sign(&subscription.secret, timestamp, &body);
+
+    let response = client
+        .post(&subscription.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(ID_HEADER, delivery.id.to_string())
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            let status_code = Some(status.as_u16() as i32);
+            if status.is_success() {
+                (status_code, None)
+            } else {
+                (
+                    status_code,
+                    Some(format!("endpoint responded with {}", status)),
+                )
+            }
+        }
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> DeliveryArgs {
+        DeliveryArgs {
+            batch_size: 100,
+            max_attempts: 10,
+            retry_base_secs: 10,
+            retry_max_secs: 3600,
+            request_timeout_secs: 10,
+            poll_interval_secs: 5,
+        }
+    }
+
+    #[test]
+    fn sign_matches_hmac_sha256() {
+        assert_eq!(
+            sign("secret", 1_700_000_000, r#"{"a":1}"#),
+            "49f24e537407743fa4a0242bb63b94b9a47ee99cbbe071ccd8a22550ae411686"
+        );
+        assert_eq!(
+            sign("other", 1_700_000_000, r#"{"a":1}"#),
+            "2cb38bd50b3aa61b12df512da616c9577f2a99edb9467110d361a655e1ad3bd5"
+        );
+    }
+
+    #[test]
+    fn sign_covers_timestamp_and_body() {
+        let signature = sign("secret", 1_700_000_000, r#"{"a":1}"#);
+        assert_ne!(sign("secret", 1_700_000_001, r#"{"a":1}"#), signature);
+        assert_ne!(sign("secret", 1_700_000_000, r#"{"a":2}"#), signature);
+    }
+
+    #[test]
+    fn retry_delay_doubles() {
+        let args = args();
+        assert_eq!(retry_delay(1, &args), Duration::seconds(10));
+        assert_eq!(retry_delay(2, &args), Duration::seconds(20));
+        assert_eq!(retry_delay(3, &args), Duration::seconds(40));
+        assert_eq!(retry_delay(6, &args), Duration::seconds(320));
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        let args = args();
+        assert_eq!(retry_delay(10, &args), Duration::seconds(3600));
+        assert_eq!(retry_delay(1_000, &args), Duration::seconds(3600));
+        assert_eq!(retry_delay(i32::MAX, &args), Duration::seconds(3600));
+    }
+
+    #[test]
+    fn retry_delay_before_any_failure() {
+        assert_eq!(retry_delay(0, &args()), Duration::seconds(10));
+    }
+}
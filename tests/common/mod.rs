@@ -0,0 +1,60 @@
+//! Helpers of the tests that need a database.
+//!
+//! `TEST_DATABASE_URL` points them to a Postgres database whose user can create databases. Every
+//! test creates a database of its own from it and drops it afterwards.
+
+use anyhow::Context;
+use diesel_async::RunQueryDsl;
+use std::env;
+use sui_indexer_alt_framework::postgres::{Db, DbArgs};
+use suins_indexer::MIGRATIONS;
+use url::Url;
+
+pub struct TestDatabase {
+    admin: Db,
+    name: String,
+    pub db: Db,
+}
+
+impl TestDatabase {
+    /// Creates a database named after the test, with every migration applied
+    pub async fn create(name: &str) -> anyhow::Result<Self> {
+        let database_url = env::var("TEST_DATABASE_URL").context("TEST_DATABASE_URL is not set")?;
+        let database_url = Url::parse(&database_url).context("invalid TEST_DATABASE_URL")?;
+
+        let name = format!(
+            "suins_test_{}_{}",
+            name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+            std::process::id()
+        );
+
+        let admin = Db::for_write(database_url.clone(), DbArgs::default()).await?;
+        let mut admin_conn = admin.connect().await?;
+        diesel::sql_query(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", name))
+            .execute(&mut admin_conn)
+            .await?;
+        diesel::sql_query(format!("CREATE DATABASE {}", name))
+            .execute(&mut admin_conn)
+            .await?;
+
+        let mut url = database_url;
+        url.set_path(&format!("/{}", name));
+
+        let db = Db::for_write(url, DbArgs::default()).await?;
+        db.run_migrations(Some(&MIGRATIONS)).await?;
+
+        Ok(Self { admin, name, db })
+    }
+
+    pub async fn remove(self) -> anyhow::Result<()> {
+        let Self { admin, name, db } = self;
+        drop(db);
+
+        let mut admin_conn = admin.connect().await?;
+        diesel::sql_query(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", name))
+            .execute(&mut admin_conn)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+//! Golden snapshot tests of the indexed tables.
+//!
+//! Every directory in `tests/fixtures` holds recorded checkpoints (see the `record` command) and
+//! a `fixture.json` with the packages they were recorded for. Each fixture is replayed through all
+//! the pipelines into a fresh database, and the tables are compared with the snapshots in its
+//! `golden` directory, so changes to the indexing show up as a diff of those files.
+//!
+//! The test needs `TEST_DATABASE_URL`, see `common`, and is ignored unless run with `--ignored`.
+//! It passes without comparing anything when no fixture is recorded. Set `UPDATE_GOLDEN=1` to
+//! write the snapshots of new fixtures, or rewrite them after an intended change.
+
+mod common;
+
+use anyhow::Context;
+use common::TestDatabase;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_indexer_alt_framework::pipeline::{concurrent, sequential, Processor};
+use sui_indexer_alt_framework::postgres::{Connection, Db};
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
+use suins_indexer::checkpoints;
+use suins_indexer::handlers::auctions_handler::AuctionsHandlerPipeline;
+use suins_indexer::handlers::name_records_handler::NameRecordsHandlerPipeline;
+use suins_indexer::handlers::offer_events_handler::OfferEventsHandlerPipeline;
+use suins_indexer::handlers::offers_handler::OffersHandlerPipeline;
+use suins_indexer::handlers::registration_owners_handler::RegistrationOwnersHandlerPipeline;
+use suins_indexer::handlers::transactions_handler::TransactionsHandlerPipeline;
+use suins_indexer::models::{
+    AcceptCounterOffer, Auction, Bid, MakeCounterOffer, Offer, OfferAccepted, OfferCancelled,
+    OfferDeclined, OfferPlaced,
+};
+use suins_indexer::schema::{
+    accept_counter_offer, auctions, bids, make_counter_offer, offer_accepted, offer_cancelled,
+    offer_declined, offer_placed, offers,
+};
+
+#[derive(Deserialize)]
+struct Fixture {
+    contract_package_id: String,
+    suins_package_id: Option<String>,
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn golden_snapshots() -> anyhow::Result<()> {
+    let update = env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+
+    // Nothing to compare until a fixture is recorded, which needs access to a checkpoint store
+    let fixture_dirs = fixture_dirs()?;
+    if fixture_dirs.is_empty() {
+        eprintln!("No fixtures found in tests/fixtures, record one with the `record` command");
+        return Ok(());
+    }
+
+    let mut mismatches = Vec::new();
+
+    for fixture_dir in fixture_dirs {
+        let name = fixture_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("invalid fixture directory name")?
+            .to_string();
+
+        let snapshot = replay_fixture(&name, &fixture_dir).await?;
+
+        let golden_dir = fixture_dir.join("golden");
+        if update {
+            fs::create_dir_all(&golden_dir)?;
+        }
+
+        for (table, actual) in snapshot {
+            let path = golden_dir.join(format!("{}.json", table));
+
+            if update {
+                fs::write(&path, &actual)?;
+                continue;
+            }
+
+            let expected = fs::read_to_string(&path).with_context(|| {
+                format!(
+                    "missing snapshot {}, run with UPDATE_GOLDEN=1",
+                    path.display()
+                )
+            })?;
+            if expected != actual {
+                mismatches.push(format!("{}: {}", name, table));
+                fs::write(golden_dir.join(format!("{}.actual.json", table)), &actual)?;
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "Indexed tables differ from their snapshots: {}. Compare the `.actual.json` files with \
+         the golden ones, and rerun with UPDATE_GOLDEN=1 if the change is intended.",
+        mismatches.join(", ")
+    );
+
+    Ok(())
+}
+
+fn fixture_dirs() -> anyhow::Result<Vec<PathBuf>> {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    if !fixtures.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(&fixtures)? {
+        let path = entry?.path();
+        if path.join("fixture.json").exists() {
+            dirs.push(path);
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+// Replays the fixture into a database of its own, dropped afterwards, and returns the snapshot of
+// every compared table
+async fn replay_fixture(
+    name: &str,
+    fixture_dir: &Path,
+) -> anyhow::Result<BTreeMap<&'static str, String>> {
+    let fixture: Fixture =
+        serde_json::from_str(&fs::read_to_string(fixture_dir.join("fixture.json"))?)?;
+
+    let database = TestDatabase::create(&format!("golden_{}", name)).await?;
+
+    let result = async {
+        let mut conn = database.db.connect().await?;
+        replay(&mut conn, &fixture, &fixture_dir.join("checkpoints")).await?;
+        snapshot(&mut conn).await
+    }
+    .await;
+
+    database.remove().await?;
+    result
+}
+
+// Checkpoints are committed one at a time, in order, by every pipeline in turn, so the result
+// doesn't depend on how the framework would have batched them
+async fn replay(
+    conn: &mut Connection<'_>,
+    fixture: &Fixture,
+    checkpoint_dir: &Path,
+) -> anyhow::Result<()> {
+    let offer_events = OfferEventsHandlerPipeline::new(fixture.contract_package_id.clone());
+    let transactions = TransactionsHandlerPipeline::new(fixture.contract_package_id.clone());
+    let offers = OffersHandlerPipeline::new(fixture.contract_package_id.clone());
+    let auctions = AuctionsHandlerPipeline::new(fixture.contract_package_id.clone());
+    let name_records = fixture
+        .suins_package_id
+        .clone()
+        .map(NameRecordsHandlerPipeline::new);
+    let registration_owners = fixture
+        .suins_package_id
+        .clone()
+        .map(RegistrationOwnersHandlerPipeline::new);
+
+    let source = checkpoints::CheckpointSource {
+        checkpoint_dir: Some(checkpoint_dir.to_path_buf()),
+        remote_store_url: None,
+        first_checkpoint: None,
+        last_checkpoint: None,
+    };
+    let client = reqwest::Client::new();
+
+    for sequence_number in source.sequence_numbers()? {
+        let checkpoint = Arc::new(source.read(&client, sequence_number).await?);
+
+        commit_concurrent(&offer_events, &checkpoint, conn).await?;
+        commit_concurrent(&transactions, &checkpoint, conn).await?;
+        commit_sequential(&offers, &checkpoint, conn).await?;
+        commit_sequential(&auctions, &checkpoint, conn).await?;
+        if let Some(name_records) = &name_records {
+            commit_sequential(name_records, &checkpoint, conn).await?;
+        }
+        if let Some(registration_owners) = &registration_owners {
+            commit_sequential(registration_owners, &checkpoint, conn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn commit_concurrent<H>(
+    pipeline: &H,
+    checkpoint: &Arc<CheckpointData>,
+    conn: &mut Connection<'_>,
+) -> anyhow::Result<()>
+where
+    H: concurrent::Handler<Store = Db>,
+{
+    let values = pipeline.process(checkpoint)?;
+    if !values.is_empty() {
+        H::commit(&values, conn).await?;
+    }
+
+    Ok(())
+}
+
+async fn commit_sequential<H>(
+    pipeline: &H,
+    checkpoint: &Arc<CheckpointData>,
+    conn: &mut Connection<'_>,
+) -> anyhow::Result<()>
+where
+    H: sequential::Handler<Store = Db>,
+    H::Batch: Default,
+{
+    let values = pipeline.process(checkpoint)?;
+
+    let mut batch = H::Batch::default();
+    H::batch(&mut batch, values);
+    H::commit(&batch, conn).await?;
+
+    Ok(())
+}
+
+// Rows are sorted by columns that don't depend on generated ids, so snapshots only change with
+// the indexing itself
+async fn snapshot(conn: &mut Connection<'_>) -> anyhow::Result<BTreeMap<&'static str, String>> {
+    let mut snapshot = BTreeMap::new();
+
+    let rows: Vec<Offer> = offers::table
+        .order((
+            offers::domain_name,
+            offers::buyer,
+            offers::coin_type,
+            offers::created_at,
+            offers::last_tx_digest,
+        ))
+        .select(Offer::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("offers", to_json(&rows)?);
+
+    let rows: Vec<Auction> = auctions::table
+        .order(auctions::auction_id)
+        .select(Auction::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("auctions", to_json(&rows)?);
+
+    let rows: Vec<Bid> = bids::table
+        .order((
+            bids::auction_id,
+            bids::created_at,
+            bids::tx_digest,
+            bids::bidder,
+            bids::amount,
+        ))
+        .select(Bid::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("bids", to_json(&rows)?);
+
+    let rows: Vec<OfferPlaced> = offer_placed::table
+        .order((
+            offer_placed::checkpoint,
+            offer_placed::tx_digest,
+            offer_placed::domain_name,
+            offer_placed::address,
+            offer_placed::value,
+        ))
+        .select(OfferPlaced::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("offer_placed", to_json(&rows)?);
+
+    let rows: Vec<OfferCancelled> = offer_cancelled::table
+        .order((
+            offer_cancelled::checkpoint,
+            offer_cancelled::tx_digest,
+            offer_cancelled::domain_name,
+            offer_cancelled::address,
+            offer_cancelled::value,
+        ))
+        .select(OfferCancelled::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("offer_cancelled", to_json(&rows)?);
+
+    let rows: Vec<OfferAccepted> = offer_accepted::table
+        .order((
+            offer_accepted::checkpoint,
+            offer_accepted::tx_digest,
+            offer_accepted::domain_name,
+            offer_accepted::address,
+            offer_accepted::value,
+        ))
+        .select(OfferAccepted::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("offer_accepted", to_json(&rows)?);
+
+    let rows: Vec<OfferDeclined> = offer_declined::table
+        .order((
+            offer_declined::checkpoint,
+            offer_declined::tx_digest,
+            offer_declined::domain_name,
+            offer_declined::address,
+            offer_declined::value,
+        ))
+        .select(OfferDeclined::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("offer_declined", to_json(&rows)?);
+
+    let rows: Vec<MakeCounterOffer> = make_counter_offer::table
+        .order((
+            make_counter_offer::checkpoint,
+            make_counter_offer::tx_digest,
+            make_counter_offer::domain_name,
+            make_counter_offer::address,
+            make_counter_offer::value,
+        ))
+        .select(MakeCounterOffer::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("make_counter_offer", to_json(&rows)?);
+
+    let rows: Vec<AcceptCounterOffer> = accept_counter_offer::table
+        .order((
+            accept_counter_offer::checkpoint,
+            accept_counter_offer::tx_digest,
+            accept_counter_offer::domain_name,
+            accept_counter_offer::address,
+            accept_counter_offer::value,
+        ))
+        .select(AcceptCounterOffer::as_select())
+        .load(conn)
+        .await?;
+    snapshot.insert("accept_counter_offer", to_json(&rows)?);
+
+    Ok(snapshot)
+}
+
+fn to_json<T: serde::Serialize>(rows: &[T]) -> anyhow::Result<String> {
+    let mut json = serde_json::to_string_pretty(rows)?;
+    json.push('\n');
+    Ok(json)
+}
@@ -0,0 +1,97 @@
+//! History of an offer changed by a pipeline that is behind the one that recorded its latest state.
+//!
+//! The test needs `TEST_DATABASE_URL`, see `common`, and is ignored unless run with `--ignored`.
+
+mod common;
+
+use common::TestDatabase;
+use diesel::internal::derives::multiconnection::chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::postgres::Connection;
+use suins_indexer::models::{Offer, OfferHistory, OfferStatus, OwnerKind, RegistrationOwner};
+use suins_indexer::projections::{history, stale_offers};
+use suins_indexer::schema::{offers, offers_history, registration_owners};
+
+const DOMAIN_NAME: &str = "example.sui";
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn lagging_pipeline() -> anyhow::Result<()> {
+    let database = TestDatabase::create("history").await?;
+
+    let result = async {
+        let mut conn = database.db.connect().await?;
+        stale_from_lagging_pipeline(&mut conn).await
+    }
+    .await;
+
+    database.remove().await?;
+    result
+}
+
+// The offers pipeline records an offer at checkpoint 20, then the registration owners pipeline,
+// still at checkpoint 10, marks it stale after a transfer of its domain
+async fn stale_from_lagging_pipeline(conn: &mut Connection<'_>) -> anyhow::Result<()> {
+    let placed_at = Utc::now() - Duration::hours(1);
+    let transferred_at = placed_at + Duration::minutes(1);
+
+    let offer_id: i32 = diesel::insert_into(offers::table)
+        .values(Offer {
+            domain_name: DOMAIN_NAME.to_string(),
+            buyer: "0xb".to_string(),
+            initial_value: "100".to_string(),
+            value: "100".to_string(),
+            owner: None,
+            status: OfferStatus::Placed,
+            updated_at: placed_at,
+            created_at: placed_at,
+            last_tx_digest: "placed".to_string(),
+            coin_type: suins_indexer::events::SUI_COIN_TYPE.to_string(),
+            stale_reason: None,
+            locked_value: "100".to_string(),
+            buyer_value: "100".to_string(),
+            counter_value: None,
+        })
+        .returning(offers::id)
+        .get_result(conn)
+        .await?;
+    history::snapshot_offer(conn, offer_id, 20).await?;
+
+    diesel::insert_into(registration_owners::table)
+        .values(RegistrationOwner {
+            nft_id: "0xn".to_string(),
+            domain_name: DOMAIN_NAME.to_string(),
+            owner_kind: OwnerKind::Address,
+            owner: Some("0xo".to_string()),
+            expiration_timestamp_ms: (Utc::now() + Duration::days(365)).timestamp_millis(),
+            checkpoint: 10,
+            last_tx_digest: "transferred".to_string(),
+            created_at: transferred_at,
+            updated_at: transferred_at,
+            holder: Some("0xo".to_string()),
+            transferred_at: Some(transferred_at),
+        })
+        .execute(conn)
+        .await?;
+    stale_offers::invalidate_stale(conn, DOMAIN_NAME, transferred_at, 10).await?;
+
+    let states: Vec<OfferHistory> = offers_history::table
+        .filter(offers_history::offer_id.eq(offer_id))
+        .order(offers_history::valid_from_checkpoint)
+        .select(OfferHistory::as_select())
+        .load(conn)
+        .await?;
+
+    // The stale state replaces the placed one at the checkpoint it was recorded at
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].status, OfferStatus::Stale);
+    assert_eq!(states[0].valid_from_checkpoint, 20);
+    assert_eq!(states[0].valid_to_checkpoint, None);
+
+    assert!(history::offer_at(conn, offer_id, 19).await?.is_none());
+    let state = history::offer_at(conn, offer_id, 20).await?;
+    assert_eq!(state.map(|state| state.status), Some(OfferStatus::Stale));
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Publication of the committed market events to a sink whose write fails once.
+//!
+//! The test needs `TEST_DATABASE_URL`, see `common`, and is ignored unless run with `--ignored`.
+
+mod common;
+
+use common::TestDatabase;
+use diesel::internal::derives::multiconnection::chrono::Utc;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel_async::RunQueryDsl;
+use std::sync::{Arc, Mutex};
+use sui_indexer_alt_framework::postgres::Connection;
+use suins_indexer::models::{MarketEvent, NewMarketEvent};
+use suins_indexer::schema::{market_events, sink_cursors};
+use suins_indexer::sinks::{self, EventSink, MemorySink, SinkArgs};
+
+// Fails once on the event with the given id, and keeps the other events in memory
+struct FlakySink {
+    fail_on: Mutex<Option<i64>>,
+    events: MemorySink,
+}
+
+impl EventSink for FlakySink {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    fn publish(&self, event: &MarketEvent) -> anyhow::Result<()> {
+        let mut fail_on = self.fail_on.lock().unwrap();
+        if *fail_on == Some(event.id) {
+            *fail_on = None;
+            anyhow::bail!("write failed");
+        }
+
+        self.events.publish(event)
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn drain_retries_failed_write() -> anyhow::Result<()> {
+    let database = TestDatabase::create("sinks").await?;
+
+    let result = async {
+        let mut conn = database.db.connect().await?;
+        retry_failed_write(&mut conn).await
+    }
+    .await;
+
+    database.remove().await?;
+    result
+}
+
+async fn retry_failed_write(conn: &mut Connection<'_>) -> anyhow::Result<()> {
+    let args = SinkArgs {
+        poll_interval_secs: 1,
+        checkpoint_span: 100,
+    };
+    let flaky = Arc::new(FlakySink {
+        fail_on: Mutex::new(None),
+        events: MemorySink::new(),
+    });
+    let sink: Arc<dyn EventSink> = flaky.clone();
+
+    // Nothing is published before the pipelines commit, and a new sink starts after what they did
+    assert!(!sinks::drain(conn, &sink, &args).await?);
+    commit(conn, 10).await?;
+    assert!(sinks::drain(conn, &sink, &args).await?);
+    assert_eq!(cursor(conn).await?, 10);
+
+    let first = publish(conn, 11, "tx1", 0).await?;
+    let second = publish(conn, 11, "tx1", 1).await?;
+    let third = publish(conn, 12, "tx2", 0).await?;
+    commit(conn, 12).await?;
+
+    // The sink keeps the position of the last checkpoint it received in full
+    *flaky.fail_on.lock().unwrap() = Some(third);
+    assert!(sinks::drain(conn, &sink, &args).await.is_err());
+    assert_eq!(cursor(conn).await?, 11);
+
+    assert!(sinks::drain(conn, &sink, &args).await?);
+    assert_eq!(cursor(conn).await?, 12);
+    assert!(!sinks::drain(conn, &sink, &args).await?);
+
+    assert_eq!(
+        flaky
+            .events
+            .events()
+            .iter()
+            .map(|event| event.id)
+            .collect::<Vec<_>>(),
+        vec![first, second, third]
+    );
+
+    Ok(())
+}
+
+// Moves the watermarks of the pipelines publishing market events to the checkpoint
+async fn commit(conn: &mut Connection<'_>, checkpoint: i64) -> anyhow::Result<()> {
+    for pipeline in suins_indexer::feed::FEED_PIPELINES {
+        diesel::sql_query(format!(
+            "INSERT INTO watermarks (pipeline, epoch_hi_inclusive, checkpoint_hi_inclusive, tx_hi, \
+             timestamp_ms_hi_inclusive, reader_lo, pruner_timestamp, pruner_hi) \
+             VALUES ('{pipeline}', 0, $1, 0, 0, 0, NOW(), 0) \
+             ON CONFLICT (pipeline) DO UPDATE SET checkpoint_hi_inclusive = $1"
+        ))
+        .bind::<BigInt, _>(checkpoint)
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn publish(
+    conn: &mut Connection<'_>,
+    checkpoint: i64,
+    tx_digest: &str,
+    event_seq: i64,
+) -> anyhow::Result<i64> {
+    let id = diesel::insert_into(market_events::table)
+        .values(NewMarketEvent {
+            event_type: "offer-placed".to_string(),
+            domain_name: "example.sui".to_string(),
+            addresses: vec![],
+            data: serde_json::json!({}),
+            coin_type: suins_indexer::events::SUI_COIN_TYPE.to_string(),
+            checkpoint,
+            tx_digest: tx_digest.to_string(),
+            created_at: Utc::now(),
+            event_seq,
+        })
+        .returning(market_events::id)
+        .get_result(conn)
+        .await?;
+
+    Ok(id)
+}
+
+async fn cursor(conn: &mut Connection<'_>) -> anyhow::Result<i64> {
+    Ok(sink_cursors::table
+        .find("flaky")
+        .select(sink_cursors::checkpoint)
+        .first(conn)
+        .await?)
+}
@@ -0,0 +1,185 @@
+//! Delivery of the webhook outbox to a local endpoint, which answers with the status code given as
+//! the path of its URL.
+//!
+//! The test needs `TEST_DATABASE_URL`, see `common`, and is ignored unless run with `--ignored`.
+
+mod common;
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use common::TestDatabase;
+use diesel::internal::derives::multiconnection::chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use sui_indexer_alt_framework::postgres::Connection;
+use suins_indexer::models::{NewWebhookDelivery, WebhookDelivery};
+use suins_indexer::schema::{webhook_outbox, webhook_subscriptions};
+use suins_indexer::webhooks::{self, DeliveryArgs};
+
+const SECRET: &str = "secret";
+
+struct Request {
+    status: u16,
+    headers: HeaderMap,
+    body: String,
+}
+
+#[derive(Clone, Default)]
+struct Received(Arc<Mutex<Vec<Request>>>);
+
+async fn respond(
+    State(received): State<Received>,
+    Path(status): Path<u16>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    received.0.lock().unwrap().push(Request {
+        status,
+        headers,
+        body,
+    });
+    StatusCode::from_u16(status).unwrap()
+}
+
+async fn start_endpoint() -> anyhow::Result<(SocketAddr, Received)> {
+    let received = Received::default();
+    let app = Router::new()
+        .route("/:status", post(respond))
+        .with_state(received.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    Ok((address, received))
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn deliver_due() -> anyhow::Result<()> {
+    let database = TestDatabase::create("webhooks").await?;
+
+    let result = async {
+        let mut conn = database.db.connect().await?;
+        deliver_retry_and_give_up(&mut conn).await
+    }
+    .await;
+
+    database.remove().await?;
+    result
+}
+
+// A delivery to a successful endpoint is delivered on its first attempt, while one to a failing
+// endpoint is retried until it runs out of attempts
+async fn deliver_retry_and_give_up(conn: &mut Connection<'_>) -> anyhow::Result<()> {
+    let (address, received) = start_endpoint().await?;
+
+    let args = DeliveryArgs {
+        batch_size: 10,
+        max_attempts: 2,
+        // Retries are due right away
+        retry_base_secs: 0,
+        retry_max_secs: 0,
+        request_timeout_secs: 5,
+        poll_interval_secs: 1,
+    };
+    let client = reqwest::Client::new();
+
+    let delivered = enqueue(conn, &format!("http://{}/200", address)).await?;
+    let failing = enqueue(conn, &format!("http://{}/500", address)).await?;
+
+    assert_eq!(webhooks::deliver_due(conn, &client, &args).await?, 2);
+
+    let delivery = load(conn, delivered).await?;
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.last_status_code, Some(200));
+    assert!(delivery.delivered_at.is_some());
+    assert!(delivery.failed_at.is_none());
+
+    let delivery = load(conn, failing).await?;
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.last_status_code, Some(500));
+    assert!(delivery.last_error.is_some());
+    assert!(delivery.delivered_at.is_none());
+    assert!(delivery.failed_at.is_none());
+
+    // The retry is the last attempt
+    assert_eq!(webhooks::deliver_due(conn, &client, &args).await?, 1);
+
+    let delivery = load(conn, failing).await?;
+    assert_eq!(delivery.attempts, 2);
+    assert!(delivery.failed_at.is_some());
+
+    assert_eq!(webhooks::deliver_due(conn, &client, &args).await?, 0);
+
+    let received = received.0.lock().unwrap();
+    assert_eq!(
+        received
+            .iter()
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![200, 500, 500]
+    );
+
+    // Requests are signed with the secret of their subscription
+    let request = &received[0];
+    let header = |name: &str| request.headers[name].to_str().unwrap().to_string();
+    let timestamp: i64 = header(webhooks::TIMESTAMP_HEADER).parse()?;
+    assert_eq!(
+        header(webhooks::SIGNATURE_HEADER),
+        format!(
+            "sha256={}",
+            webhooks::sign(SECRET, timestamp, &request.body)
+        )
+    );
+    assert_eq!(header(webhooks::ID_HEADER), delivered.to_string());
+    assert_eq!(header(webhooks::EVENT_HEADER), "offer-placed");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&request.body)?,
+        serde_json::json!({ "domain_name": "example.sui" })
+    );
+
+    Ok(())
+}
+
+// Adds a subscription with the given URL and a due delivery to it, returning the delivery's id
+async fn enqueue(conn: &mut Connection<'_>, url: &str) -> anyhow::Result<i64> {
+    let subscription_id: i32 = diesel::insert_into(webhook_subscriptions::table)
+        .values((
+            webhook_subscriptions::url.eq(url),
+            webhook_subscriptions::secret.eq(SECRET),
+        ))
+        .returning(webhook_subscriptions::id)
+        .get_result(conn)
+        .await?;
+
+    let now = Utc::now();
+    let id = diesel::insert_into(webhook_outbox::table)
+        .values(NewWebhookDelivery {
+            subscription_id,
+            event_type: "offer-placed".to_string(),
+            domain_name: "example.sui".to_string(),
+            payload: serde_json::json!({ "domain_name": "example.sui" }),
+            next_attempt_at: now - Duration::seconds(1),
+            checkpoint: 1,
+            tx_digest: "tx".to_string(),
+            created_at: now,
+        })
+        .returning(webhook_outbox::id)
+        .get_result(conn)
+        .await?;
+
+    Ok(id)
+}
+
+async fn load(conn: &mut Connection<'_>, id: i64) -> anyhow::Result<WebhookDelivery> {
+    Ok(webhook_outbox::table
+        .find(id)
+        .select(WebhookDelivery::as_select())
+        .first(conn)
+        .await?)
+}